@@ -0,0 +1,217 @@
+use crate::RzCore;
+use crate::error::{Error, Result, with_log};
+use crate::util::{RzList, RzPVector, cstr_to_string, owned_cstr_to_string};
+use crate::xref::{XRef, XRefKind, collect_xrefs};
+use std::mem::ManuallyDrop;
+use std::ptr::null;
+
+pub(crate) fn addr_opt(addr: u64) -> Option<u64> {
+    if addr == u64::MAX { None } else { Some(addr) }
+}
+
+/// Snapshot of a basic block, copied out of rizin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    pub addr: u64,
+    pub size: u64,
+    pub ninstr: usize,
+    pub jump: Option<u64>,
+    pub fail: Option<u64>,
+    pub switch_cases: Vec<SwitchCase>,
+}
+
+impl From<&rizin_sys::RzAnalysisBlock> for BasicBlock {
+    fn from(bb: &rizin_sys::RzAnalysisBlock) -> Self {
+        let switch_cases = unsafe { bb.switch_op.as_ref() }
+            .and_then(|op| RzList::<rizin_sys::RzAnalysisCaseOp>::from_raw(op.cases))
+            .map(ManuallyDrop::new)
+            .map(|cases| {
+                cases
+                    .iter()
+                    .map(|c| SwitchCase {
+                        value: c.value,
+                        jump: c.jump,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            addr: bb.addr,
+            size: bb.size,
+            ninstr: bb.ninstr as _,
+            jump: addr_opt(bb.jump),
+            fail: addr_opt(bb.fail),
+            switch_cases,
+        }
+    }
+}
+
+impl BasicBlock {
+    pub fn contains(&self, addr: u64) -> bool {
+        (self.addr..self.addr + self.size).contains(&addr)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwitchCase {
+    pub value: u64,
+    pub jump: u64,
+}
+
+/// Snapshot of an analyzed function. It stays valid when rizin later drops or
+/// re-analyzes the function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    pub name: String,
+    pub addr: u64,
+    /// Distance from the lowest to the highest address covered by the function's blocks.
+    pub size: u64,
+    /// Sum of the sizes of the function's blocks.
+    pub real_size: u64,
+    pub calling_convention: Option<String>,
+    pub stack_frame_size: i64,
+    pub is_noreturn: bool,
+    pub signature: Option<String>,
+    pub blocks: Vec<BasicBlock>,
+    pub xrefs_to: Vec<XRef>,
+    pub xrefs_from: Vec<XRef>,
+}
+
+impl Function {
+    pub(crate) fn from_raw(fcn: *mut rizin_sys::RzAnalysisFunction) -> Option<Self> {
+        let raw = unsafe { fcn.as_ref()? };
+        let blocks = RzPVector::<rizin_sys::RzAnalysisBlock>::from_raw(raw.bbs)
+            .map(ManuallyDrop::new)
+            .map(|bbs| {
+                bbs.iter()
+                    .filter_map(|bb| unsafe { bb.as_ref() })
+                    .map(BasicBlock::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        unsafe {
+            Some(Self {
+                name: cstr_to_string(raw.name).unwrap_or_default(),
+                addr: raw.addr,
+                size: rizin_sys::rz_analysis_function_linear_size(fcn),
+                real_size: rizin_sys::rz_analysis_function_realsize(fcn),
+                calling_convention: cstr_to_string(raw.cc),
+                stack_frame_size: raw.maxstack as _,
+                is_noreturn: raw.is_noreturn,
+                signature: owned_cstr_to_string(rizin_sys::rz_analysis_function_get_signature(fcn)),
+                blocks,
+                xrefs_to: collect_xrefs(rizin_sys::rz_analysis_function_get_xrefs_to(fcn)),
+                xrefs_from: collect_xrefs(rizin_sys::rz_analysis_function_get_xrefs_from(fcn)),
+            })
+        }
+    }
+
+    /// Functions containing a call to this function.
+    pub fn callers(&self, core: &RzCore) -> Vec<Function> {
+        let mut callers: Vec<Function> = self
+            .xrefs_to
+            .iter()
+            .filter(|x| x.kind == XRefKind::Call)
+            .filter_map(|x| core.function_containing(x.from))
+            .collect();
+        callers.sort_by_key(|f| f.addr);
+        callers.dedup_by_key(|f| f.addr);
+        callers
+    }
+
+    /// Analyzed functions called from this function.
+    pub fn callees(&self, core: &RzCore) -> Vec<Function> {
+        let mut callees: Vec<Function> = self
+            .xrefs_from
+            .iter()
+            .filter(|x| x.kind == XRefKind::Call)
+            .filter_map(|x| core.function_at(x.to))
+            .collect();
+        callees.sort_by_key(|f| f.addr);
+        callees.dedup_by_key(|f| f.addr);
+        callees
+    }
+}
+
+impl RzCore {
+    pub(crate) fn analysis(&self) -> *mut rizin_sys::RzAnalysis {
+        unsafe { self.0.as_ref().analysis }
+    }
+
+    pub fn analyze_all(&self) -> Result<()> {
+        let (ok, log) = with_log(|| unsafe { rizin_sys::rz_core_analysis_all(self.0.as_ptr()) });
+        if ok {
+            Ok(())
+        } else {
+            Err(Error::AnalysisFailed { addr: None, log })
+        }
+    }
+
+    pub fn analyze_function(&self, addr: u64) -> Result<Function> {
+        let (ok, log) = with_log(|| unsafe {
+            rizin_sys::rz_core_analysis_function_add(self.0.as_ptr(), null(), addr, true)
+        });
+        let fcn = if ok { self.function_at(addr) } else { None };
+        fcn.ok_or(Error::AnalysisFailed {
+            addr: Some(addr),
+            log,
+        })
+    }
+
+    pub fn functions(&self) -> Vec<Function> {
+        RzList::<rizin_sys::RzAnalysisFunction>::from_raw(unsafe {
+            rizin_sys::rz_analysis_function_list(self.analysis())
+        })
+        .map(ManuallyDrop::new)
+        .map(|fcns| {
+            fcns.iter()
+                .filter_map(|f| Function::from_raw(f as *const _ as _))
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn function_at(&self, addr: u64) -> Option<Function> {
+        Function::from_raw(unsafe { rizin_sys::rz_analysis_get_function_at(self.analysis(), addr) })
+    }
+
+    pub fn function_containing(&self, addr: u64) -> Option<Function> {
+        Function::from_raw(unsafe { rizin_sys::rz_analysis_get_fcn_in(self.analysis(), addr, 0) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RzCore;
+    use crate::test_util::load_code;
+
+    #[test]
+    fn test_analyze_function() {
+        let core = RzCore::new();
+        let _file = load_code(&core);
+        let main = core.analyze_function(0x1000).unwrap();
+        let f = core.function_at(0x1006).unwrap();
+        assert_eq!(main.addr, 0x1000);
+        assert_eq!(f.name, "fcn.00001006");
+        assert_eq!(f.size, 6);
+        assert_eq!(core.function_containing(0x100a).unwrap().addr, 0x1006);
+
+        let mut blocks: Vec<_> = f.blocks.iter().map(|bb| (bb.addr, bb.size)).collect();
+        blocks.sort();
+        assert_eq!(blocks, vec![(0x1006, 4), (0x100a, 1), (0x100b, 1)]);
+        let head = f.blocks.iter().find(|bb| bb.addr == 0x1006).unwrap();
+        assert_eq!(head.jump, Some(0x100b));
+        assert_eq!(head.fail, Some(0x100a));
+        assert!(head.contains(0x1008));
+
+        let callers: Vec<_> = f.callers(&core).iter().map(|c| c.addr).collect();
+        assert_eq!(callers, vec![0x1000]);
+        let callees: Vec<_> = main.callees(&core).iter().map(|c| c.addr).collect();
+        assert_eq!(callees, vec![0x1006]);
+
+        // The snapshot outlives the function in rizin.
+        core.cmd("af-*").unwrap();
+        assert!(core.function_at(0x1006).is_none());
+        assert_eq!(f.blocks.len(), 3);
+    }
+}
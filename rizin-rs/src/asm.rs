@@ -0,0 +1,119 @@
+use crate::RzCore;
+use crate::error::{Error, Result, with_log};
+use std::ffi::CString;
+use std::fmt;
+use std::slice;
+
+/// Assembly failure pointing at the offending source line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmError {
+    /// 1-based line number in the assembled text.
+    pub line: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: `{}`: {}", self.line, self.text, self.reason)
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+/// Statements the assembler emits code for, skipping blanks, comments, labels and directives.
+fn statements(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines().enumerate().flat_map(|(i, line)| {
+        line.split(';')
+            .map(str::trim)
+            .filter(|s| !s.is_empty() && !s.starts_with(['#', '.']) && !s.ends_with(':'))
+            .map(move |s| (i + 1, s))
+    })
+}
+
+impl RzCore {
+    pub(crate) fn rasm(&self) -> *mut rizin_sys::RzAsm {
+        unsafe { self.0.as_ref().rasm }
+    }
+
+    /// Assembles `text` at `addr` for the configured `asm.arch`, `asm.bits` and `asm.cpu`.
+    /// Statements are separated by newlines or `;`. On failure `line` points at the
+    /// offending statement when it could be located.
+    pub fn assemble(&self, text: &str, addr: u64) -> Result<Vec<u8>> {
+        let ctext = CString::new(text)?;
+        let (code, log) = with_log(|| unsafe {
+            rizin_sys::rz_asm_set_pc(self.rasm(), addr);
+            rizin_sys::rz_asm_massemble(self.rasm(), ctext.as_ptr())
+        });
+        if let Some(code) = unsafe { code.as_ref() } {
+            let bytes = if code.bytes.is_null() || code.len <= 0 {
+                Vec::new()
+            } else {
+                unsafe { slice::from_raw_parts(code.bytes, code.len as usize) }.to_vec()
+            };
+            unsafe { rizin_sys::rz_asm_code_free(code as *const _ as *mut _) };
+            return Ok(bytes);
+        }
+        Err(Error::AssembleFailed {
+            addr,
+            line: self.locate_asm_error(text, addr),
+            log,
+        })
+    }
+
+    /// Assembles statement by statement to find the first one that fails on its own.
+    fn locate_asm_error(&self, text: &str, addr: u64) -> Option<AsmError> {
+        let mut pc = addr;
+        for (line, stmt) in statements(text) {
+            let failed = |reason: &str| AsmError {
+                line,
+                text: stmt.to_owned(),
+                reason: reason.to_owned(),
+            };
+            let Ok(cstmt) = CString::new(stmt) else {
+                return Some(failed("contains nul byte"));
+            };
+            let (size, log) = with_log(|| unsafe {
+                let op = rizin_sys::rz_asm_op_new();
+                rizin_sys::rz_asm_set_pc(self.rasm(), pc);
+                let size = rizin_sys::rz_asm_assemble(self.rasm(), op, cstmt.as_ptr());
+                rizin_sys::rz_asm_op_free(op);
+                size
+            });
+            if size <= 0 {
+                return Some(failed(
+                    log.as_deref()
+                        .unwrap_or("invalid instruction for the configured arch"),
+                ));
+            }
+            pc += size as u64;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+    use crate::RzCore;
+
+    #[test]
+    fn test_assemble() {
+        let core = RzCore::new();
+        core.config_set("asm.arch", "x86").unwrap();
+        core.config_set("asm.bits", "32").unwrap();
+        assert_eq!(
+            core.assemble("nop\nadd eax, ebx; ret", 0x1000).unwrap(),
+            vec![0x90, 0x01, 0xd8, 0xc3]
+        );
+
+        let Err(Error::AssembleFailed {
+            line: Some(err), ..
+        }) = core.assemble("nop\nnot_an_insn eax\nret", 0x1000)
+        else {
+            panic!("expected located assemble error");
+        };
+        assert_eq!(err.line, 2);
+        assert_eq!(err.text, "not_an_insn eax");
+    }
+}
@@ -0,0 +1,413 @@
+use crate::buf::RzBuf;
+use crate::error::{Error, Result, with_log};
+use crate::util::{RzPVector, cstr_to_string};
+use crate::{Perm, RzCore};
+use std::ffi::{CString, c_char};
+use std::io::{Read, Seek};
+use std::mem::ManuallyDrop;
+use std::path::Path;
+use std::ptr::{NonNull, addr_of_mut, null};
+use std::slice;
+
+#[derive(Debug, Clone, Default)]
+pub struct BinOptions {
+    pub base_addr: u64,
+    pub load_addr: u64,
+    pub plugin: Option<String>,
+    pub patch_relocs: bool,
+}
+
+impl BinOptions {
+    pub(crate) fn with_raw<R>(
+        &self,
+        f: impl FnOnce(&mut rizin_sys::RzBinOptions) -> R,
+    ) -> Result<R> {
+        let plugin = self.plugin.as_deref().map(CString::new).transpose()?;
+        let mut opt = rizin_sys::RzBinOptions::default();
+        unsafe {
+            rizin_sys::rz_bin_options_init(
+                addr_of_mut!(opt),
+                0,
+                self.base_addr,
+                self.load_addr,
+                self.patch_relocs,
+            );
+        }
+        opt.pluginname = plugin.as_ref().map_or(null(), |p| p.as_ptr());
+        Ok(f(&mut opt))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub name: String,
+    pub vaddr: u64,
+    pub paddr: u64,
+    pub size: u64,
+    pub vsize: u64,
+    pub perm: Perm,
+}
+
+impl From<&rizin_sys::RzBinSection> for Section {
+    fn from(s: &rizin_sys::RzBinSection) -> Self {
+        Self {
+            name: cstr_to_string(s.name).unwrap_or_default(),
+            vaddr: s.vaddr,
+            paddr: s.paddr,
+            size: s.size,
+            vsize: s.vsize,
+            perm: Perm(s.perm as _),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub demangled: Option<String>,
+    pub libname: Option<String>,
+    pub bind: Option<String>,
+    pub kind: Option<String>,
+    pub vaddr: u64,
+    pub paddr: u64,
+    pub size: u64,
+    pub ordinal: u32,
+    pub is_imported: bool,
+}
+
+impl Symbol {
+    pub fn is_exported(&self) -> bool {
+        !self.is_imported && matches!(self.bind.as_deref(), Some("GLOBAL" | "WEAK"))
+    }
+}
+
+impl From<&rizin_sys::RzBinSymbol> for Symbol {
+    fn from(s: &rizin_sys::RzBinSymbol) -> Self {
+        Self {
+            name: cstr_to_string(s.name).unwrap_or_default(),
+            demangled: cstr_to_string(s.dname),
+            libname: cstr_to_string(s.libname),
+            bind: cstr_to_string(s.bind),
+            kind: cstr_to_string(s.type_),
+            vaddr: s.vaddr,
+            paddr: s.paddr,
+            size: s.size as _,
+            ordinal: s.ordinal as _,
+            is_imported: s.is_imported,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    pub name: String,
+    pub libname: Option<String>,
+    pub bind: Option<String>,
+    pub kind: Option<String>,
+    pub ordinal: u32,
+}
+
+impl From<&rizin_sys::RzBinImport> for Import {
+    fn from(i: &rizin_sys::RzBinImport) -> Self {
+        Self {
+            name: cstr_to_string(i.name).unwrap_or_default(),
+            libname: cstr_to_string(i.libname),
+            bind: cstr_to_string(i.bind),
+            kind: cstr_to_string(i.type_),
+            ordinal: i.ordinal as _,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Program,
+    Main,
+    Init,
+    Fini,
+    Tls,
+    Preinit,
+    Unknown(i32),
+}
+
+impl From<i32> for EntryKind {
+    fn from(value: i32) -> Self {
+        match value as u32 {
+            rizin_sys::RZ_BIN_ENTRY_TYPE_PROGRAM => EntryKind::Program,
+            rizin_sys::RZ_BIN_ENTRY_TYPE_MAIN => EntryKind::Main,
+            rizin_sys::RZ_BIN_ENTRY_TYPE_INIT => EntryKind::Init,
+            rizin_sys::RZ_BIN_ENTRY_TYPE_FINI => EntryKind::Fini,
+            rizin_sys::RZ_BIN_ENTRY_TYPE_TLS => EntryKind::Tls,
+            rizin_sys::RZ_BIN_ENTRY_TYPE_PREINIT => EntryKind::Preinit,
+            _ => EntryKind::Unknown(value),
+        }
+    }
+}
+
+impl From<EntryKind> for i32 {
+    fn from(value: EntryKind) -> Self {
+        (match value {
+            EntryKind::Program => rizin_sys::RZ_BIN_ENTRY_TYPE_PROGRAM,
+            EntryKind::Main => rizin_sys::RZ_BIN_ENTRY_TYPE_MAIN,
+            EntryKind::Init => rizin_sys::RZ_BIN_ENTRY_TYPE_INIT,
+            EntryKind::Fini => rizin_sys::RZ_BIN_ENTRY_TYPE_FINI,
+            EntryKind::Tls => rizin_sys::RZ_BIN_ENTRY_TYPE_TLS,
+            EntryKind::Preinit => rizin_sys::RZ_BIN_ENTRY_TYPE_PREINIT,
+            EntryKind::Unknown(v) => return v,
+        }) as i32
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryPoint {
+    pub vaddr: u64,
+    pub paddr: u64,
+    pub kind: EntryKind,
+    pub bits: u32,
+}
+
+impl From<&rizin_sys::RzBinAddr> for EntryPoint {
+    fn from(a: &rizin_sys::RzBinAddr) -> Self {
+        Self {
+            vaddr: a.vaddr,
+            paddr: a.paddr,
+            kind: EntryKind::from(a.type_),
+            bits: a.bits as _,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reloc {
+    pub vaddr: u64,
+    pub paddr: u64,
+    pub target_vaddr: u64,
+    pub addend: i64,
+    /// Width of the patched field in bits.
+    pub bits: u32,
+    pub symbol: Option<String>,
+    pub is_ifunc: bool,
+}
+
+impl From<&rizin_sys::RzBinReloc> for Reloc {
+    fn from(r: &rizin_sys::RzBinReloc) -> Self {
+        let symbol = unsafe {
+            r.symbol
+                .as_ref()
+                .and_then(|s| cstr_to_string(s.name))
+                .or_else(|| r.import.as_ref().and_then(|i| cstr_to_string(i.name)))
+        };
+        Self {
+            vaddr: r.vaddr,
+            paddr: r.paddr,
+            target_vaddr: r.target_vaddr,
+            addend: r.addend as _,
+            bits: r.type_ as _,
+            symbol,
+            is_ifunc: r.is_ifunc,
+        }
+    }
+}
+
+pub struct RzBinFile<'a> {
+    core: &'a RzCore,
+    pub inner: NonNull<rizin_sys::RzBinFile>,
+}
+
+impl Drop for RzBinFile<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            rizin_sys::rz_bin_file_delete(self.core.0.as_ref().bin, self.inner.as_ptr());
+        }
+    }
+}
+
+impl<'a> RzBinFile<'a> {
+    pub(crate) fn new(core: &'a RzCore, bf: *mut rizin_sys::RzBinFile) -> Option<Self> {
+        NonNull::new(bf).map(|inner| Self { core, inner })
+    }
+
+    pub fn from_buf(core: &'a RzCore, buf: &RzBuf, options: &BinOptions) -> Result<Self> {
+        let (bf, log) = with_log(|| {
+            options.with_raw(|opt| unsafe {
+                rizin_sys::rz_bin_open_buf(core.0.as_ref().bin, buf.as_ptr(), opt)
+            })
+        });
+        Self::new(core, bf?).ok_or(Error::OpenFailed {
+            path: "<buffer>".to_owned(),
+            log,
+        })
+    }
+
+    pub fn from_bytes(
+        core: &'a RzCore,
+        bytes: impl AsRef<[u8]>,
+        options: &BinOptions,
+    ) -> Result<Self> {
+        Self::from_buf(core, &RzBuf::from_bytes(bytes.as_ref()), options)
+    }
+
+    /// Parses straight from `reader`; rizin reads only the parts it needs.
+    pub fn from_reader<R: Read + Seek + 'static>(
+        core: &'a RzCore,
+        reader: R,
+        options: &BinOptions,
+    ) -> Result<Self> {
+        Self::from_buf(core, &RzBuf::from_reader(reader), options)
+    }
+
+    fn object(&self) -> Option<NonNull<rizin_sys::RzBinObject>> {
+        NonNull::new(unsafe { self.inner.as_ref().o })
+    }
+
+    fn collect<R, T>(
+        &self,
+        get: unsafe extern "C" fn(*mut rizin_sys::RzBinObject) -> *const rizin_sys::RzPVector,
+    ) -> Vec<T>
+    where
+        T: for<'x> From<&'x R>,
+    {
+        self.object()
+            .and_then(|o| RzPVector::<R>::from_raw(unsafe { get(o.as_ptr()) } as _))
+            .map(ManuallyDrop::new)
+            .map(|v| {
+                v.iter()
+                    .filter_map(|x| unsafe { x.as_ref() })
+                    .map(T::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn collect_owned<R, T>(
+        &self,
+        get: unsafe extern "C" fn(*mut rizin_sys::RzBinObject) -> *mut rizin_sys::RzPVector,
+    ) -> Vec<T>
+    where
+        T: for<'x> From<&'x R>,
+    {
+        self.object()
+            .and_then(|o| RzPVector::<R>::from_raw(unsafe { get(o.as_ptr()) }))
+            .map(|v| {
+                v.iter()
+                    .filter_map(|x| unsafe { x.as_ref() })
+                    .map(T::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn sections(&self) -> Vec<Section> {
+        self.collect_owned::<rizin_sys::RzBinSection, _>(rizin_sys::rz_bin_object_get_sections)
+    }
+
+    pub fn segments(&self) -> Vec<Section> {
+        self.collect_owned::<rizin_sys::RzBinSection, _>(rizin_sys::rz_bin_object_get_segments)
+    }
+
+    pub fn symbols(&self) -> Vec<Symbol> {
+        self.collect::<rizin_sys::RzBinSymbol, _>(rizin_sys::rz_bin_object_get_symbols)
+    }
+
+    pub fn imports(&self) -> Vec<Import> {
+        self.collect::<rizin_sys::RzBinImport, _>(rizin_sys::rz_bin_object_get_imports)
+    }
+
+    pub fn exports(&self) -> Vec<Symbol> {
+        self.symbols()
+            .into_iter()
+            .filter(Symbol::is_exported)
+            .collect()
+    }
+
+    pub fn entrypoints(&self) -> Vec<EntryPoint> {
+        self.collect::<rizin_sys::RzBinAddr, _>(rizin_sys::rz_bin_object_get_entries)
+    }
+
+    pub fn libraries(&self) -> Vec<String> {
+        self.object()
+            .and_then(|o| {
+                RzPVector::<c_char>::from_raw(unsafe {
+                    rizin_sys::rz_bin_object_get_libs(o.as_ptr())
+                } as _)
+            })
+            .map(ManuallyDrop::new)
+            .map(|v| v.iter().filter_map(|x| cstr_to_string(*x)).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn relocs(&self) -> Vec<Reloc> {
+        self.object()
+            .and_then(|o| unsafe { rizin_sys::rz_bin_object_get_relocs(o.as_ptr()).as_ref() })
+            .filter(|rs| !rs.relocs.is_null())
+            .map(|rs| unsafe { slice::from_raw_parts(rs.relocs, rs.relocs_count as _) })
+            .map(|relocs| {
+                relocs
+                    .iter()
+                    .filter_map(|r| unsafe { r.as_ref() })
+                    .map(Reloc::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl RzCore {
+    pub fn open_bin(&self, path: impl AsRef<Path>, options: &BinOptions) -> Result<RzBinFile<'_>> {
+        let path = path.as_ref();
+        let spath = path
+            .to_str()
+            .ok_or_else(|| Error::InvalidPath(path.to_owned()))?;
+        let cpath = CString::new(spath)?;
+        let (bf, log) = with_log(|| {
+            options.with_raw(|opt| unsafe {
+                rizin_sys::rz_bin_open(self.0.as_ref().bin, cpath.as_ptr(), opt)
+            })
+        });
+        RzBinFile::new(self, bf?).ok_or(Error::OpenFailed {
+            path: spath.to_owned(),
+            log,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RzCore;
+    use crate::bin::{BinOptions, RzBinFile};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_open_bin() {
+        let core = RzCore::new();
+        let exe = std::env::current_exe().unwrap();
+        let bf = core.open_bin(&exe, &BinOptions::default()).unwrap();
+        assert!(!bf.sections().is_empty());
+        assert!(!bf.symbols().is_empty());
+        assert!(!bf.entrypoints().is_empty());
+        assert!(bf.exports().iter().all(|s| !s.is_imported));
+    }
+
+    #[test]
+    fn test_open_bin_bytes() {
+        let core = RzCore::new();
+        let bytes = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let from_path = core
+            .open_bin(std::env::current_exe().unwrap(), &BinOptions::default())
+            .unwrap()
+            .sections();
+        let bf = RzBinFile::from_bytes(&core, &bytes, &BinOptions::default()).unwrap();
+        assert_eq!(bf.sections(), from_path);
+        let bf = RzBinFile::from_reader(&core, Cursor::new(bytes), &BinOptions::default()).unwrap();
+        assert_eq!(bf.sections(), from_path);
+    }
+
+    #[test]
+    fn test_open_bin_missing() {
+        let core = RzCore::new();
+        assert!(
+            core.open_bin("/nonexistent/binary", &BinOptions::default())
+                .is_err()
+        );
+    }
+}
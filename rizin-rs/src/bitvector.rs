@@ -0,0 +1,414 @@
+use crate::util::owned_cstr_to_string;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops;
+use std::ptr::NonNull;
+
+/// Fixed-width bitvector.
+///
+/// # Panics
+///
+/// Binary operators panic when the operands' lengths differ, as do
+/// [`BitVector::sdiv`] and [`BitVector::smod`]. The `checked_*` methods return
+/// `None` instead.
+pub struct BitVector(pub(crate) NonNull<rizin_sys::RzBitVector>);
+
+unsafe impl Send for BitVector {}
+unsafe impl Sync for BitVector {}
+
+impl Drop for BitVector {
+    fn drop(&mut self) {
+        unsafe {
+            rizin_sys::rz_bv_free(self.0.as_ptr());
+        }
+    }
+}
+
+impl Clone for BitVector {
+    fn clone(&self) -> Self {
+        unsafe { Self::from_raw_dup(self.0.as_ptr()) }.expect("null ptr")
+    }
+}
+
+impl BitVector {
+    /// Takes ownership of a bitvector allocated by rizin.
+    pub(crate) fn from_raw(bv: *mut rizin_sys::RzBitVector) -> Option<Self> {
+        NonNull::new(bv).map(Self)
+    }
+
+    /// Copies a bitvector still owned by rizin.
+    pub(crate) unsafe fn from_raw_dup(bv: *const rizin_sys::RzBitVector) -> Option<Self> {
+        if bv.is_null() {
+            None
+        } else {
+            Self::from_raw(unsafe { rizin_sys::rz_bv_dup(bv) })
+        }
+    }
+
+    pub(crate) fn into_raw(self) -> *mut rizin_sys::RzBitVector {
+        let ptr = self.0.as_ptr();
+        std::mem::forget(self);
+        ptr
+    }
+
+    /// Zeroed bitvector of `len` bits.
+    pub fn new(len: u32) -> Self {
+        Self::from_raw(unsafe { rizin_sys::rz_bv_new(len) }).expect("null ptr")
+    }
+
+    pub fn from_u64(len: u32, value: u64) -> Self {
+        Self::from_raw(unsafe { rizin_sys::rz_bv_new_from_ut64(len, value) }).expect("null ptr")
+    }
+
+    pub fn from_u128(len: u32, value: u128) -> Self {
+        Self::from_bytes_le(len, &value.to_le_bytes())
+    }
+
+    /// Bitvector of `len` bits read from little-endian `bytes`, zero-extended if short.
+    pub fn from_bytes_le(len: u32, bytes: &[u8]) -> Self {
+        let mut buf = bytes.to_vec();
+        buf.resize(len.div_ceil(8) as usize, 0);
+        Self::from_raw(unsafe { rizin_sys::rz_bv_new_from_bytes_le(buf.as_ptr(), 0, len) })
+            .expect("null ptr")
+    }
+
+    /// Bitvector of `len` bits read from big-endian `bytes`, zero-extended if short.
+    pub fn from_bytes_be(len: u32, bytes: &[u8]) -> Self {
+        let mut le = bytes.to_vec();
+        le.reverse();
+        Self::from_bytes_le(len, &le)
+    }
+
+    pub fn len(&self) -> u32 {
+        unsafe { rizin_sys::rz_bv_len(self.0.as_ptr()) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Low 64 bits of the value.
+    pub fn to_u64(&self) -> u64 {
+        unsafe { rizin_sys::rz_bv_to_ut64(self.0.as_ptr()) }
+    }
+
+    /// Low 128 bits of the value.
+    pub fn to_u128(&self) -> u128 {
+        let mut bytes = [0u8; 16];
+        self.to_bytes_le()
+            .iter()
+            .zip(bytes.iter_mut())
+            .for_each(|(b, o)| *o = *b);
+        u128::from_le_bytes(bytes)
+    }
+
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; self.len().div_ceil(8) as usize];
+        unsafe { rizin_sys::rz_bv_set_to_bytes_le(self.0.as_ptr(), bytes.as_mut_ptr()) };
+        bytes
+    }
+
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    pub fn get(&self, pos: u32) -> bool {
+        unsafe { rizin_sys::rz_bv_get(self.0.as_ptr(), pos) }
+    }
+
+    pub fn set(&mut self, pos: u32, bit: bool) {
+        unsafe { rizin_sys::rz_bv_set(self.0.as_ptr(), pos, bit) };
+    }
+
+    pub fn msb(&self) -> bool {
+        unsafe { rizin_sys::rz_bv_msb(self.0.as_ptr()) }
+    }
+
+    pub fn lsb(&self) -> bool {
+        unsafe { rizin_sys::rz_bv_lsb(self.0.as_ptr()) }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        unsafe { rizin_sys::rz_bv_is_zero_vector(self.0.as_ptr()) }
+    }
+
+    /// Unsigned less-or-equal.
+    pub fn ule(&self, other: &Self) -> bool {
+        unsafe { rizin_sys::rz_bv_ule(self.0.as_ptr(), other.0.as_ptr()) }
+    }
+
+    /// Signed less-or-equal.
+    pub fn sle(&self, other: &Self) -> bool {
+        unsafe { rizin_sys::rz_bv_sle(self.0.as_ptr(), other.0.as_ptr()) }
+    }
+
+    pub fn sdiv(&self, other: &Self) -> Self {
+        self.checked_sdiv(other).expect("bitvector length mismatch")
+    }
+
+    pub fn checked_sdiv(&self, other: &Self) -> Option<Self> {
+        Self::from_raw(unsafe { rizin_sys::rz_bv_sdiv(self.0.as_ptr(), other.0.as_ptr()) })
+    }
+
+    pub fn smod(&self, other: &Self) -> Self {
+        self.checked_smod(other).expect("bitvector length mismatch")
+    }
+
+    pub fn checked_smod(&self, other: &Self) -> Option<Self> {
+        Self::from_raw(unsafe { rizin_sys::rz_bv_smod(self.0.as_ptr(), other.0.as_ptr()) })
+    }
+
+    pub fn as_ptr(&self) -> *mut rizin_sys::RzBitVector {
+        self.0.as_ptr()
+    }
+}
+
+impl PartialEq for BitVector {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && unsafe { rizin_sys::rz_bv_eq(self.0.as_ptr(), other.0.as_ptr()) }
+    }
+}
+
+impl Eq for BitVector {}
+
+impl PartialOrd for BitVector {
+    /// Unsigned comparison; bitvectors of different lengths are unordered.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.len() != other.len() {
+            None
+        } else if self == other {
+            Some(Ordering::Equal)
+        } else if self.ule(other) {
+            Some(Ordering::Less)
+        } else {
+            Some(Ordering::Greater)
+        }
+    }
+}
+
+macro_rules! bv_binop {
+    ($trait:ident, $method:ident, $checked:ident, |$x:ident, $y:ident| $body:expr) => {
+        impl BitVector {
+            #[doc = concat!("`", stringify!($method), "`, or `None` if the lengths differ.")]
+            pub fn $checked(&self, rhs: &BitVector) -> Option<BitVector> {
+                let ($x, $y) = (self.0.as_ptr(), rhs.0.as_ptr());
+                BitVector::from_raw(unsafe { $body })
+            }
+        }
+
+        impl ops::$trait<&BitVector> for &BitVector {
+            type Output = BitVector;
+
+            fn $method(self, rhs: &BitVector) -> BitVector {
+                self.$checked(rhs).expect("bitvector length mismatch")
+            }
+        }
+
+        impl ops::$trait for BitVector {
+            type Output = BitVector;
+
+            fn $method(self, rhs: BitVector) -> BitVector {
+                ops::$trait::$method(&self, &rhs)
+            }
+        }
+    };
+}
+
+bv_binop!(Add, add, checked_add, |x, y| rizin_sys::rz_bv_add(
+    x,
+    y,
+    std::ptr::null_mut()
+));
+bv_binop!(Sub, sub, checked_sub, |x, y| rizin_sys::rz_bv_sub(
+    x,
+    y,
+    std::ptr::null_mut()
+));
+bv_binop!(Mul, mul, checked_mul, |x, y| rizin_sys::rz_bv_mul(x, y));
+bv_binop!(Div, div, checked_div, |x, y| rizin_sys::rz_bv_div(x, y));
+bv_binop!(Rem, rem, checked_rem, |x, y| rizin_sys::rz_bv_mod(x, y));
+bv_binop!(BitAnd, bitand, checked_and, |x, y| rizin_sys::rz_bv_and(
+    x, y
+));
+bv_binop!(BitOr, bitor, checked_or, |x, y| rizin_sys::rz_bv_or(x, y));
+bv_binop!(BitXor, bitxor, checked_xor, |x, y| rizin_sys::rz_bv_xor(
+    x, y
+));
+
+impl ops::Not for &BitVector {
+    type Output = BitVector;
+
+    fn not(self) -> BitVector {
+        BitVector::from_raw(unsafe { rizin_sys::rz_bv_not(self.0.as_ptr()) }).expect("null ptr")
+    }
+}
+
+impl ops::Not for BitVector {
+    type Output = BitVector;
+
+    fn not(self) -> BitVector {
+        !&self
+    }
+}
+
+impl ops::Neg for &BitVector {
+    type Output = BitVector;
+
+    /// Two's complement negation.
+    fn neg(self) -> BitVector {
+        BitVector::from_raw(unsafe { rizin_sys::rz_bv_complement_2(self.0.as_ptr()) })
+            .expect("null ptr")
+    }
+}
+
+impl ops::Neg for BitVector {
+    type Output = BitVector;
+
+    fn neg(self) -> BitVector {
+        -&self
+    }
+}
+
+impl ops::Shl<u32> for &BitVector {
+    type Output = BitVector;
+
+    fn shl(self, rhs: u32) -> BitVector {
+        let bv = self.clone();
+        unsafe { rizin_sys::rz_bv_lshift(bv.0.as_ptr(), rhs) };
+        bv
+    }
+}
+
+impl ops::Shl<u32> for BitVector {
+    type Output = BitVector;
+
+    fn shl(self, rhs: u32) -> BitVector {
+        unsafe { rizin_sys::rz_bv_lshift(self.0.as_ptr(), rhs) };
+        self
+    }
+}
+
+impl ops::Shr<u32> for &BitVector {
+    type Output = BitVector;
+
+    /// Logical shift right.
+    fn shr(self, rhs: u32) -> BitVector {
+        let bv = self.clone();
+        unsafe { rizin_sys::rz_bv_rshift(bv.0.as_ptr(), rhs) };
+        bv
+    }
+}
+
+impl ops::Shr<u32> for BitVector {
+    type Output = BitVector;
+
+    fn shr(self, rhs: u32) -> BitVector {
+        unsafe { rizin_sys::rz_bv_rshift(self.0.as_ptr(), rhs) };
+        self
+    }
+}
+
+impl fmt::Display for BitVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for BitVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s =
+            owned_cstr_to_string(unsafe { rizin_sys::rz_bv_as_hex_string(self.0.as_ptr(), false) })
+                .ok_or(fmt::Error)?;
+        let digits = s.trim_start_matches("0x");
+        f.pad_integral(true, "0x", digits)
+    }
+}
+
+impl fmt::Binary for BitVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = owned_cstr_to_string(unsafe { rizin_sys::rz_bv_as_string(self.0.as_ptr()) })
+            .ok_or(fmt::Error)?;
+        f.pad_integral(true, "0b", &s)
+    }
+}
+
+impl From<bool> for BitVector {
+    fn from(value: bool) -> Self {
+        Self::from_u64(1, value as u64)
+    }
+}
+
+impl From<u8> for BitVector {
+    fn from(value: u8) -> Self {
+        Self::from_u64(8, value as u64)
+    }
+}
+
+impl From<u16> for BitVector {
+    fn from(value: u16) -> Self {
+        Self::from_u64(16, value as u64)
+    }
+}
+
+impl From<u32> for BitVector {
+    fn from(value: u32) -> Self {
+        Self::from_u64(32, value as u64)
+    }
+}
+
+impl From<u64> for BitVector {
+    fn from(value: u64) -> Self {
+        Self::from_u64(64, value)
+    }
+}
+
+impl From<u128> for BitVector {
+    fn from(value: u128) -> Self {
+        Self::from_u128(128, value)
+    }
+}
+
+impl fmt::Debug for BitVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BitVector")
+            .field("len", &self.len())
+            .field("bytes_le", &self.to_bytes_le())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitvector::BitVector;
+
+    #[test]
+    fn test_bitvector_ops() {
+        let x = BitVector::from(0xf0u8);
+        let y = BitVector::from(0x11u8);
+        assert_eq!((&x + &y).to_u64(), 0x01);
+        assert_eq!((&y - &x).to_u64(), 0x21);
+        assert_eq!((&x & &y).to_u64(), 0x10);
+        assert_eq!((&x | &y).to_u64(), 0xf1);
+        assert_eq!((&x ^ &y).to_u64(), 0xe1);
+        assert_eq!((!&x).to_u64(), 0x0f);
+        assert_eq!((-&y).to_u64(), 0xef);
+        assert_eq!((&y << 4).to_u64(), 0x10);
+        assert_eq!((&x >> 4).to_u64(), 0x0f);
+        assert!(y < x);
+        assert!(x.sle(&y));
+        assert_eq!(format!("{:x}", x), "f0");
+        assert_eq!(format!("{:#x}", x), "0xf0");
+        assert_eq!(format!("{:b}", y), "00010001");
+
+        let wide = BitVector::from_u128(100, 1 << 90);
+        assert_eq!(wide.to_u128(), 1 << 90);
+        assert_eq!(BitVector::from_bytes_be(16, &[0x12, 0x34]).to_u64(), 0x1234);
+        assert_eq!(BitVector::from(0x1234u16).to_bytes_be(), vec![0x12, 0x34]);
+
+        assert_eq!(x.checked_add(&y), Some(&x + &y));
+        assert_eq!(x.checked_add(&BitVector::from(1u16)), None);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::error::{Error, Result, with_log};
+use std::ffi::{CString, c_int, c_void};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::ptr::{NonNull, addr_of};
+use std::slice;
+
+pub struct RzBuf(pub(crate) NonNull<rizin_sys::RzBuffer>);
+
+impl Drop for RzBuf {
+    fn drop(&mut self) {
+        unsafe {
+            rizin_sys::rz_buf_free(self.0.as_ptr());
+        }
+    }
+}
+
+impl RzBuf {
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let buf = unsafe { rizin_sys::rz_buf_new_with_bytes(bytes.as_ptr(), bytes.len() as _) };
+        Self(NonNull::new(buf).expect("null ptr"))
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let spath = path
+            .to_str()
+            .ok_or_else(|| Error::InvalidPath(path.to_owned()))?;
+        let cpath = CString::new(spath)?;
+        let (buf, log) = with_log(|| unsafe { rizin_sys::rz_buf_new_slurp(cpath.as_ptr()) });
+        NonNull::new(buf).map(Self).ok_or(Error::OpenFailed {
+            path: spath.to_owned(),
+            log,
+        })
+    }
+
+    /// Wraps `io` so rizin reads, writes and seeks through it.
+    pub fn from_io<T: Read + Write + Seek + 'static>(io: T) -> Self {
+        IoBuf::new(io, Some(IoBuf::<T>::write))
+    }
+
+    /// Like [`RzBuf::from_io`], but writes to the buffer fail.
+    pub fn from_reader<T: Read + Seek + 'static>(io: T) -> Self {
+        IoBuf::new(io, None)
+    }
+
+    /// Returns a view of `size` bytes at `offset`, sharing the underlying storage.
+    pub fn slice(&self, offset: u64, size: u64) -> Result<Self> {
+        let buf = unsafe { rizin_sys::rz_buf_new_slice(self.0.as_ptr(), offset, size) };
+        NonNull::new(buf)
+            .map(Self)
+            .ok_or_else(|| Error::rizin(format!("slice {:#x}+{:#x}", offset, size), None))
+    }
+
+    pub fn len(&self) -> u64 {
+        unsafe { rizin_sys::rz_buf_size(self.0.as_ptr()) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn read_at(&self, addr: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let res = unsafe {
+            rizin_sys::rz_buf_read_at(self.0.as_ptr(), addr, buf.as_mut_ptr(), buf.len() as _)
+        };
+        usize::try_from(res).map_err(|_| io::Error::other("rz_buf_read_at failed"))
+    }
+
+    pub fn as_ptr(&self) -> *mut rizin_sys::RzBuffer {
+        self.0.as_ptr()
+    }
+}
+
+impl Read for RzBuf {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let res =
+            unsafe { rizin_sys::rz_buf_read(self.0.as_ptr(), buf.as_mut_ptr(), buf.len() as _) };
+        usize::try_from(res).map_err(|_| io::Error::other("rz_buf_read failed"))
+    }
+}
+
+impl Write for RzBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let res = unsafe { rizin_sys::rz_buf_write(self.0.as_ptr(), buf.as_ptr(), buf.len() as _) };
+        usize::try_from(res).map_err(|_| io::Error::other("rz_buf_write failed"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for RzBuf {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (addr, whence) = match pos {
+            SeekFrom::Start(n) => (n as i64, rizin_sys::RZ_BUF_SET),
+            SeekFrom::Current(n) => (n, rizin_sys::RZ_BUF_CUR),
+            SeekFrom::End(n) => (n, rizin_sys::RZ_BUF_END),
+        };
+        let res = unsafe { rizin_sys::rz_buf_seek(self.0.as_ptr(), addr, whence as _) };
+        u64::try_from(res).map_err(|_| io::Error::other("rz_buf_seek failed"))
+    }
+}
+
+type WriteFn = unsafe extern "C" fn(*mut rizin_sys::RzBuffer, *const u8, u64) -> i64;
+
+struct IoBuf<T> {
+    methods: rizin_sys::RzBufferMethods,
+    io: T,
+}
+
+impl<T: Read + Seek + 'static> IoBuf<T> {
+    fn new(io: T, write: Option<WriteFn>) -> RzBuf {
+        let state = Box::into_raw(Box::new(IoBuf {
+            methods: rizin_sys::RzBufferMethods {
+                init: Some(Self::init),
+                fini: Some(Self::fini),
+                read: Some(Self::read),
+                write,
+                get_size: Some(Self::get_size),
+                seek: Some(Self::seek),
+                ..Default::default()
+            },
+            io,
+        }));
+        let buf =
+            unsafe { rizin_sys::rz_buf_new_with_methods(addr_of!((*state).methods), state as _) };
+        RzBuf(NonNull::new(buf).expect("null ptr"))
+    }
+
+    unsafe fn io<'a>(b: *mut rizin_sys::RzBuffer) -> &'a mut T {
+        unsafe { &mut (*((*b).priv_ as *mut Self)).io }
+    }
+
+    unsafe extern "C" fn init(b: *mut rizin_sys::RzBuffer, user: *const c_void) -> bool {
+        unsafe {
+            (*b).priv_ = user as _;
+        }
+        true
+    }
+
+    unsafe extern "C" fn fini(b: *mut rizin_sys::RzBuffer) -> bool {
+        unsafe {
+            drop(Box::from_raw((*b).priv_ as *mut Self));
+        }
+        true
+    }
+
+    unsafe extern "C" fn read(b: *mut rizin_sys::RzBuffer, buf: *mut u8, len: u64) -> i64 {
+        let buf = unsafe { slice::from_raw_parts_mut(buf, len as _) };
+        unsafe { Self::io(b) }.read(buf).map_or(-1, |n| n as _)
+    }
+
+    unsafe extern "C" fn get_size(b: *mut rizin_sys::RzBuffer) -> u64 {
+        Self::stream_len(unsafe { Self::io(b) }).unwrap_or(0)
+    }
+
+    fn stream_len(io: &mut T) -> io::Result<u64> {
+        let cur = io.stream_position()?;
+        let end = io.seek(SeekFrom::End(0))?;
+        io.seek(SeekFrom::Start(cur))?;
+        Ok(end)
+    }
+
+    unsafe extern "C" fn seek(b: *mut rizin_sys::RzBuffer, addr: i64, whence: c_int) -> i64 {
+        let pos = match whence as u32 {
+            rizin_sys::RZ_BUF_SET => SeekFrom::Start(addr as _),
+            rizin_sys::RZ_BUF_CUR => SeekFrom::Current(addr),
+            rizin_sys::RZ_BUF_END => SeekFrom::End(addr),
+            _ => return -1,
+        };
+        unsafe { Self::io(b) }.seek(pos).map_or(-1, |n| n as _)
+    }
+}
+
+impl<T: Read + Write + Seek + 'static> IoBuf<T> {
+    unsafe extern "C" fn write(b: *mut rizin_sys::RzBuffer, buf: *const u8, len: u64) -> i64 {
+        let buf = unsafe { slice::from_raw_parts(buf, len as _) };
+        unsafe { Self::io(b) }.write(buf).map_or(-1, |n| n as _)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buf::RzBuf;
+    use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+
+    #[test]
+    fn test_buf_bytes() {
+        let mut buf = RzBuf::from_bytes(b"hello world");
+        assert_eq!(buf.len(), 11);
+        let mut s = String::new();
+        buf.read_to_string(&mut s).unwrap();
+        assert_eq!(s, "hello world");
+        buf.seek(SeekFrom::Start(6)).unwrap();
+        buf.write_all(b"rizin").unwrap();
+        let mut out = [0u8; 11];
+        buf.read_at(0, &mut out).unwrap();
+        assert_eq!(&out, b"hello rizin");
+    }
+
+    #[test]
+    fn test_buf_slice() {
+        let buf = RzBuf::from_bytes(b"hello world");
+        let mut sub = buf.slice(6, 5).unwrap();
+        assert_eq!(sub.len(), 5);
+        let mut out = Vec::new();
+        sub.read_to_end(&mut out).unwrap();
+        assert_eq!(out, b"world");
+    }
+
+    #[test]
+    fn test_buf_io() {
+        let mut buf = RzBuf::from_io(Cursor::new(b"0123456789".to_vec()));
+        assert_eq!(buf.len(), 10);
+        assert_eq!(buf.seek(SeekFrom::End(-3)).unwrap(), 7);
+        let mut out = [0u8; 3];
+        buf.read_exact(&mut out).unwrap();
+        assert_eq!(&out, b"789");
+        buf.seek(SeekFrom::Start(0)).unwrap();
+        buf.write_all(b"ab").unwrap();
+        let mut out = [0u8; 4];
+        buf.read_at(0, &mut out).unwrap();
+        assert_eq!(&out, b"ab23");
+
+        let mut ro = RzBuf::from_reader(Cursor::new(b"ro".to_vec()));
+        assert!(ro.write(b"x").is_err());
+    }
+}
@@ -0,0 +1,386 @@
+use crate::RzCore;
+use crate::error::{Error, Result, with_log};
+use crate::util::cstr_to_string;
+use std::collections::HashMap;
+use std::ffi::{CString, c_char, c_int};
+use std::mem::ManuallyDrop;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr::{NonNull, null};
+use std::slice;
+use std::sync::{Arc, Mutex, OnceLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    String,
+    /// Numeric expression, evaluated with [`CmdArgs::num`].
+    Number,
+    Flag,
+    File,
+}
+
+impl From<ArgKind> for rizin_sys::RzCmdArgType {
+    fn from(kind: ArgKind) -> Self {
+        match kind {
+            ArgKind::String => rizin_sys::RZ_CMD_ARG_TYPE_STRING,
+            ArgKind::Number => rizin_sys::RZ_CMD_ARG_TYPE_RZNUM,
+            ArgKind::Flag => rizin_sys::RZ_CMD_ARG_TYPE_FLAG,
+            ArgKind::File => rizin_sys::RZ_CMD_ARG_TYPE_FILE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CmdArg {
+    pub name: String,
+    pub kind: ArgKind,
+    pub optional: bool,
+}
+
+/// Name, help and arguments of a command added with [`RzCore::register_command`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandSpec {
+    pub name: String,
+    /// One line shown by `?`.
+    pub summary: String,
+    pub description: Option<String>,
+    pub args: Vec<CmdArg>,
+    /// Also accept `<name>j`, run with [`OutputMode::Json`].
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    Standard,
+    Json,
+}
+
+/// Arguments a command was invoked with, in [`CommandSpec::args`] order.
+pub struct CmdArgs<'a> {
+    core: &'a RzCore,
+    spec: &'a CommandSpec,
+    argv: Vec<String>,
+}
+
+impl CmdArgs<'_> {
+    pub fn len(&self) -> usize {
+        self.argv.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.argv.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        let idx = self.spec.args.iter().position(|a| a.name == name)?;
+        self.argv.get(idx).map(String::as_str)
+    }
+
+    /// Evaluates the argument as a rizin expression, so flags and math work.
+    pub fn num(&self, name: &str) -> Option<u64> {
+        let arg = CString::new(self.get(name)?).ok()?;
+        let num = unsafe { self.core.0.as_ref().num };
+        unsafe { rizin_sys::rz_num_is_valid_input(num, arg.as_ptr()) }
+            .then(|| unsafe { rizin_sys::rz_num_math(num, arg.as_ptr()) })
+    }
+}
+
+type Handler = dyn Fn(&RzCore, &CmdArgs, OutputMode) -> Result<String> + Send + Sync;
+
+struct Command {
+    spec: CommandSpec,
+    handler: Arc<Handler>,
+    desc: NonNull<rizin_sys::RzCmdDesc>,
+    // Help and argument tables rizin points into for as long as the command exists.
+    _help: Box<rizin_sys::RzCmdDescHelp>,
+    _args: Vec<rizin_sys::RzCmdDescArg>,
+    _strings: Vec<CString>,
+}
+
+// The raw pointers are only dereferenced by rizin on the thread running the core.
+unsafe impl Send for Command {}
+
+/// Commands by core address and name. rizin's callbacks carry no user data, so
+/// the handler is found from the core and `argv[0]`.
+fn commands() -> &'static Mutex<HashMap<(usize, String), Command>> {
+    static COMMANDS: OnceLock<Mutex<HashMap<(usize, String), Command>>> = OnceLock::new();
+    COMMANDS.get_or_init(Default::default)
+}
+
+/// Drops the handlers of a core being freed.
+pub(crate) fn forget_core(core: *mut rizin_sys::RzCore) {
+    commands()
+        .lock()
+        .unwrap()
+        .retain(|(c, _), _| *c != core as usize);
+}
+
+unsafe extern "C" fn run_command(
+    core: *mut rizin_sys::RzCore,
+    argc: c_int,
+    argv: *const *const c_char,
+    state: *mut rizin_sys::RzCmdStateOutput,
+) -> rizin_sys::RzCmdStatus {
+    let (Some(core), Some(state)) = (NonNull::new(core), unsafe { state.as_mut() }) else {
+        return rizin_sys::RZ_CMD_STATUS_INVALID;
+    };
+    let argv: Vec<String> = unsafe { slice::from_raw_parts(argv, argc.max(0) as _) }
+        .iter()
+        .map(|&arg| cstr_to_string(arg).unwrap_or_default())
+        .collect();
+    let Some(name) = argv.first() else {
+        return rizin_sys::RZ_CMD_STATUS_INVALID;
+    };
+    let mode = if state.mode == rizin_sys::RZ_OUTPUT_MODE_JSON {
+        OutputMode::Json
+    } else {
+        OutputMode::Standard
+    };
+    // argv[0] may carry the mode suffix, as in `<name>j`.
+    let found = {
+        let commands = commands().lock().unwrap();
+        let key = |name: &str| (core.as_ptr() as usize, name.to_owned());
+        commands
+            .get(&key(name))
+            .or_else(|| {
+                let base = name
+                    .strip_suffix('j')
+                    .filter(|_| mode == OutputMode::Json)?;
+                commands.get(&key(base))
+            })
+            .map(|c| (c.spec.clone(), c.handler.clone()))
+    };
+    let Some((spec, handler)) = found else {
+        return rizin_sys::RZ_CMD_STATUS_INVALID;
+    };
+    // Borrowed from rizin, never freed here.
+    let core = ManuallyDrop::new(RzCore(core));
+    let args = CmdArgs {
+        core: &core,
+        spec: &spec,
+        argv: argv[1..].to_vec(),
+    };
+    // Unwinding into rizin's C frames is undefined, so a panic fails the command.
+    let Ok(out) = panic::catch_unwind(AssertUnwindSafe(|| {
+        handler(&core, &args, mode).and_then(|out| Ok(CString::new(out)?))
+    })) else {
+        let msg = CString::new(format!("command {} panicked", spec.name)).unwrap_or_default();
+        unsafe { rizin_sys::rz_cons_eprintf(c"%s\n".as_ptr(), msg.as_ptr()) };
+        return rizin_sys::RZ_CMD_STATUS_ERROR;
+    };
+    match out {
+        Ok(out) => {
+            unsafe {
+                match mode {
+                    OutputMode::Json => rizin_sys::pj_raw(state.d.pj, out.as_ptr()),
+                    OutputMode::Standard => rizin_sys::rz_cons_print(out.as_ptr()),
+                };
+            }
+            rizin_sys::RZ_CMD_STATUS_OK
+        }
+        Err(e) => {
+            let msg = CString::new(e.to_string()).unwrap_or_default();
+            unsafe { rizin_sys::rz_cons_eprintf(c"%s\n".as_ptr(), msg.as_ptr()) };
+            rizin_sys::RZ_CMD_STATUS_ERROR
+        }
+    }
+}
+
+impl RzCore {
+    /// Runs a rizin command and returns what it printed. Output goes to a pushed
+    /// cons buffer, so the process stdout is left alone. Unknown or failing
+    /// commands are errors.
+    pub fn cmd(&self, cmd: &str) -> Result<String> {
+        let ccmd = CString::new(cmd)?;
+        let ((status, out), log) = with_log(|| unsafe {
+            let context = (*rizin_sys::rz_cons_singleton()).context;
+            let noflush = (*context).noflush;
+            rizin_sys::rz_cons_push();
+            (*context).noflush = true;
+            let status = rizin_sys::rz_core_cmd_rzshell(self.0.as_ptr(), ccmd.as_ptr(), false);
+            (*context).noflush = noflush;
+            rizin_sys::rz_cons_filter();
+            let out = cstr_to_string(rizin_sys::rz_cons_get_buffer()).unwrap_or_default();
+            rizin_sys::rz_cons_pop();
+            (status, out)
+        });
+        match status {
+            rizin_sys::RZ_CMD_STATUS_OK | rizin_sys::RZ_CMD_STATUS_EXIT => Ok(out),
+            _ => Err(Error::CommandFailed {
+                cmd: cmd.to_owned(),
+                log,
+            }),
+        }
+    }
+
+    /// Runs a `j` command and parses its output.
+    #[cfg(feature = "serde")]
+    pub fn cmd_json(&self, cmd: &str) -> Result<serde_json::Value> {
+        Ok(serde_json::from_str(&self.cmd(cmd)?)?)
+    }
+
+    fn rcmd(&self) -> *mut rizin_sys::RzCmd {
+        unsafe { self.0.as_ref().rcmd }
+    }
+
+    /// Adds a top level command to the shell of this core. The handler's output
+    /// is printed, its error is shown and the command reports failure.
+    pub fn register_command(
+        &self,
+        spec: CommandSpec,
+        handler: impl Fn(&RzCore, &CmdArgs, OutputMode) -> Result<String> + Send + Sync + 'static,
+    ) -> Result<()> {
+        let name = CString::new(spec.name.as_str())?;
+        if !unsafe { rizin_sys::rz_cmd_get_desc(self.rcmd(), name.as_ptr()) }.is_null() {
+            return Err(Error::rizin(
+                format!("register command {}: already exists", spec.name),
+                None,
+            ));
+        }
+
+        let mut strings = vec![CString::new(spec.summary.as_str())?];
+        if let Some(desc) = &spec.description {
+            strings.push(CString::new(desc.as_str())?);
+        }
+        let mut args = Vec::with_capacity(spec.args.len() + 1);
+        for arg in &spec.args {
+            let arg_name = CString::new(arg.name.as_str())?;
+            args.push(rizin_sys::RzCmdDescArg {
+                name: arg_name.as_ptr(),
+                optional: arg.optional,
+                type_: arg.kind.into(),
+                ..Default::default()
+            });
+            strings.push(arg_name);
+        }
+        // Terminator.
+        args.push(Default::default());
+        let help = Box::new(rizin_sys::RzCmdDescHelp {
+            summary: strings[0].as_ptr(),
+            description: spec
+                .description
+                .as_ref()
+                .map_or(null(), |_| strings[1].as_ptr()),
+            args: args.as_ptr(),
+            ..Default::default()
+        });
+
+        let mut modes = rizin_sys::RZ_OUTPUT_MODE_STANDARD;
+        if spec.json {
+            modes |= rizin_sys::RZ_OUTPUT_MODE_JSON;
+        }
+        let (desc, log) = with_log(|| unsafe {
+            rizin_sys::rz_cmd_desc_argv_state_new(
+                self.rcmd(),
+                rizin_sys::rz_cmd_get_root(self.rcmd()),
+                name.as_ptr(),
+                modes as _,
+                Some(run_command),
+                &*help,
+            )
+        });
+        let desc = NonNull::new(desc)
+            .ok_or_else(|| Error::rizin(format!("register command {}", spec.name), log))?;
+        commands().lock().unwrap().insert(
+            (self.0.as_ptr() as usize, spec.name.clone()),
+            Command {
+                spec,
+                handler: Arc::new(handler),
+                desc,
+                _help: help,
+                _args: args,
+                _strings: strings,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn unregister_command(&self, name: &str) -> Result<()> {
+        let command = commands()
+            .lock()
+            .unwrap()
+            .remove(&(self.0.as_ptr() as usize, name.to_owned()))
+            .ok_or_else(|| Error::rizin(format!("unregister command {}", name), None))?;
+        unsafe { rizin_sys::rz_cmd_desc_remove(self.rcmd(), command.desc.as_ptr()) };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::{ArgKind, CmdArg, CommandSpec, OutputMode};
+    use crate::{Error, RzCore};
+
+    #[test]
+    fn test_cmd() {
+        let core = RzCore::new();
+        assert_eq!(core.cmd("?e hello").unwrap(), "hello\n");
+        core.cmd("e asm.bits=32").unwrap();
+        assert_eq!(core.config_get_u64("asm.bits").unwrap(), 32);
+        assert!(matches!(
+            core.cmd("not_a_command_xyz"),
+            Err(Error::CommandFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_register_command() {
+        let core = RzCore::new();
+        let spec = CommandSpec {
+            name: "rsinc".into(),
+            summary: "Print an address plus one".into(),
+            args: vec![CmdArg {
+                name: "addr".into(),
+                kind: ArgKind::Number,
+                optional: false,
+            }],
+            json: true,
+            ..Default::default()
+        };
+        core.register_command(spec.clone(), |_core, args, mode| {
+            let addr = args
+                .num("addr")
+                .ok_or_else(|| Error::rizin("bad address", None))?;
+            Ok(match mode {
+                OutputMode::Standard => format!("{:#x}\n", addr + 1),
+                OutputMode::Json => format!("{{\"addr\":{}}}", addr + 1),
+            })
+        })
+        .unwrap();
+        assert!(
+            core.register_command(spec, |_, _, _| Ok(String::new()))
+                .is_err()
+        );
+
+        core.add_flag("sym.foo", 0x1000, 1).unwrap();
+        assert_eq!(core.cmd("rsinc sym.foo+0x10").unwrap(), "0x1011\n");
+        assert_eq!(core.cmd("rsincj 1").unwrap().trim(), "{\"addr\":2}");
+        assert!(matches!(
+            core.cmd("rsinc not_a_flag_xyz"),
+            Err(Error::CommandFailed { .. })
+        ));
+
+        core.unregister_command("rsinc").unwrap();
+        assert!(core.unregister_command("rsinc").is_err());
+
+        let spec = CommandSpec {
+            name: "rspanic".into(),
+            summary: "Panic".into(),
+            ..Default::default()
+        };
+        core.register_command(spec, |_, _, _| panic!("boom"))
+            .unwrap();
+        assert!(matches!(
+            core.cmd("rspanic"),
+            Err(Error::CommandFailed { .. })
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_cmd_json() {
+        let core = RzCore::new();
+        core.config_set("asm.arch", "x86").unwrap();
+        let config = core.cmd_json("ej").unwrap();
+        assert_eq!(config["asm.arch"], "x86");
+    }
+}
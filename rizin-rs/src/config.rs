@@ -0,0 +1,252 @@
+use crate::RzCore;
+use crate::error::{Error, Result, with_log};
+use crate::util::{RzList, cstr_to_string};
+use rizin_sys::{CN_BOOL, CN_INT, CN_OFFT, CN_RO, CN_STR};
+use std::ffi::{CStr, CString};
+use std::mem::ManuallyDrop;
+use std::ptr::NonNull;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConfigKind {
+    Bool,
+    Int,
+    Offset,
+    Str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfigNode {
+    pub name: String,
+    pub desc: Option<String>,
+    pub kind: ConfigKind,
+    pub value: String,
+    /// Allowed values, empty when the node accepts anything of its kind.
+    pub options: Vec<String>,
+    pub read_only: bool,
+}
+
+impl ConfigKind {
+    fn of(n: &rizin_sys::RzConfigNode) -> Self {
+        let flags = n.flags as u32;
+        if flags & CN_BOOL != 0 {
+            ConfigKind::Bool
+        } else if flags & CN_OFFT != 0 {
+            ConfigKind::Offset
+        } else if flags & CN_INT != 0 {
+            ConfigKind::Int
+        } else {
+            debug_assert!(flags & CN_STR != 0 || flags & !CN_RO == 0);
+            ConfigKind::Str
+        }
+    }
+}
+
+impl From<&rizin_sys::RzConfigNode> for ConfigNode {
+    fn from(n: &rizin_sys::RzConfigNode) -> Self {
+        let flags = n.flags as u32;
+        Self {
+            name: cstr_to_string(n.name).unwrap_or_default(),
+            desc: cstr_to_string(n.desc),
+            kind: ConfigKind::of(n),
+            value: cstr_to_string(n.value).unwrap_or_default(),
+            options: RzList::<std::ffi::c_char>::from_raw(n.options)
+                .map(ManuallyDrop::new)
+                .map(|opts| {
+                    opts.iter()
+                        .filter_map(|o| cstr_to_string(o as *const _))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            read_only: flags & CN_RO != 0,
+        }
+    }
+}
+
+/// Writable settings in definition order, so restoring replays dependent keys
+/// (e.g. `asm.arch` before `asm.bits`) the way they were set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfigSnapshot {
+    pub values: Vec<(String, String)>,
+}
+
+impl RzCore {
+    fn config(&self) -> *mut rizin_sys::RzConfig {
+        unsafe { self.0.as_ref().config }
+    }
+
+    fn config_node(&self, key: &str) -> Result<NonNull<rizin_sys::RzConfigNode>> {
+        let ckey = CString::new(key)?;
+        NonNull::new(unsafe { rizin_sys::rz_config_node_get(self.config(), ckey.as_ptr()) })
+            .ok_or_else(|| Error::UnknownConfigKey(key.to_owned()))
+    }
+
+    /// Fails unless `key` holds one of `kinds`, so typed accessors never read
+    /// or write a value rizin would reinterpret.
+    fn config_check_kind(&self, key: &str, kinds: &[ConfigKind]) -> Result<()> {
+        let kind = ConfigKind::of(unsafe { self.config_node(key)?.as_ref() });
+        if kinds.contains(&kind) {
+            Ok(())
+        } else {
+            Err(Error::ConfigKindMismatch {
+                key: key.to_owned(),
+                kind,
+            })
+        }
+    }
+
+    pub fn config_set(&self, k: &str, v: &str) -> Result<&Self> {
+        let ck = CString::new(k)?;
+        let cv = CString::new(v)?;
+        let (node, log) = with_log(|| unsafe {
+            rizin_sys::rz_config_set(self.config(), ck.as_ptr(), cv.as_ptr())
+        });
+        match NonNull::new(node) {
+            Some(_) => Ok(self),
+            None if self.config_node(k).is_ok() => {
+                Err(Error::rizin(format!("config set {}={}", k, v), log))
+            }
+            None => Err(Error::UnknownConfigKey(k.to_owned())),
+        }
+    }
+
+    pub fn config_get(&self, key: &str) -> Result<String> {
+        self.config_node(key)?;
+        let ckey = CString::new(key)?;
+        let ptr = unsafe { rizin_sys::rz_config_get(self.config(), ckey.as_ptr()) };
+        if ptr.is_null() {
+            return Err(Error::NullPointer("config value"));
+        }
+        Ok(unsafe { CStr::from_ptr(ptr) }.to_str()?.to_owned())
+    }
+
+    pub fn config_get_bool(&self, key: &str) -> Result<bool> {
+        self.config_check_kind(key, &[ConfigKind::Bool])?;
+        let ckey = CString::new(key)?;
+        Ok(unsafe { rizin_sys::rz_config_get_b(self.config(), ckey.as_ptr()) })
+    }
+
+    pub fn config_get_u64(&self, key: &str) -> Result<u64> {
+        self.config_check_kind(key, &[ConfigKind::Int, ConfigKind::Offset])?;
+        let ckey = CString::new(key)?;
+        Ok(unsafe { rizin_sys::rz_config_get_i(self.config(), ckey.as_ptr()) })
+    }
+
+    pub fn config_get_i64(&self, key: &str) -> Result<i64> {
+        self.config_get_u64(key).map(|v| v as i64)
+    }
+
+    pub fn config_set_bool(&self, key: &str, value: bool) -> Result<&Self> {
+        self.config_check_kind(key, &[ConfigKind::Bool])?;
+        let ckey = CString::new(key)?;
+        let (node, log) =
+            with_log(|| unsafe { rizin_sys::rz_config_set_b(self.config(), ckey.as_ptr(), value) });
+        NonNull::new(node)
+            .map(|_| self)
+            .ok_or_else(|| Error::rizin(format!("config set {}={}", key, value), log))
+    }
+
+    pub fn config_set_u64(&self, key: &str, value: u64) -> Result<&Self> {
+        self.config_check_kind(key, &[ConfigKind::Int, ConfigKind::Offset])?;
+        let ckey = CString::new(key)?;
+        let (node, log) =
+            with_log(|| unsafe { rizin_sys::rz_config_set_i(self.config(), ckey.as_ptr(), value) });
+        NonNull::new(node)
+            .map(|_| self)
+            .ok_or_else(|| Error::rizin(format!("config set {}={}", key, value), log))
+    }
+
+    pub fn config_set_i64(&self, key: &str, value: i64) -> Result<&Self> {
+        self.config_set_u64(key, value as u64)
+    }
+
+    pub fn config_node_info(&self, key: &str) -> Result<ConfigNode> {
+        self.config_node(key)
+            .map(|node| ConfigNode::from(unsafe { node.as_ref() }))
+    }
+
+    pub fn config_nodes(&self) -> Vec<ConfigNode> {
+        RzList::<rizin_sys::RzConfigNode>::from_raw(unsafe { (*self.config()).nodes })
+            .map(ManuallyDrop::new)
+            .map(|nodes| nodes.iter().map(ConfigNode::from).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn config_snapshot(&self) -> ConfigSnapshot {
+        ConfigSnapshot {
+            values: self
+                .config_nodes()
+                .into_iter()
+                .filter(|n| !n.read_only)
+                .map(|n| (n.name, n.value))
+                .collect(),
+        }
+    }
+
+    /// Applies every value in `snapshot`, continuing past failures and
+    /// returning the first one.
+    pub fn config_restore(&self, snapshot: &ConfigSnapshot) -> Result<()> {
+        snapshot
+            .values
+            .iter()
+            .map(|(k, v)| self.config_set(k, v).map(|_| ()))
+            .fold(Ok(()), |acc, res| acc.and(res))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RzCore;
+    use crate::config::ConfigKind;
+    use crate::error::Error;
+
+    #[test]
+    fn test_config_typed() {
+        let core = RzCore::new();
+        core.config_set_bool("asm.bytes", false).unwrap();
+        assert!(!core.config_get_bool("asm.bytes").unwrap());
+        core.config_set_u64("asm.bits", 32).unwrap();
+        assert_eq!(core.config_get_u64("asm.bits").unwrap(), 32);
+        assert_eq!(core.config_get("asm.bits").unwrap(), "32");
+        assert!(matches!(
+            core.config_get("no.such.key"),
+            Err(Error::UnknownConfigKey(_))
+        ));
+        assert!(matches!(
+            core.config_get_bool("asm.bits"),
+            Err(Error::ConfigKindMismatch {
+                kind: ConfigKind::Int,
+                ..
+            })
+        ));
+        assert!(core.config_get_u64("asm.arch").is_err());
+        assert!(core.config_set_u64("asm.bytes", 1).is_err());
+        assert!(core.config_set_bool("asm.arch", true).is_err());
+
+        let bits = core.config_node_info("asm.bits").unwrap();
+        assert_eq!(bits.kind, ConfigKind::Int);
+        assert!(bits.desc.is_some());
+        assert!(core.config_nodes().iter().any(|n| n.name == "asm.arch"));
+    }
+
+    #[test]
+    fn test_config_snapshot() {
+        let core = RzCore::new();
+        core.config_set("asm.arch", "arm").unwrap();
+        core.config_set_u64("asm.bits", 16).unwrap();
+        let mut snapshot = core.config_snapshot();
+        snapshot.values.retain(|(k, _)| k.starts_with("asm."));
+        assert!(
+            snapshot
+                .values
+                .contains(&("asm.arch".to_owned(), "arm".to_owned()))
+        );
+
+        let other = RzCore::new();
+        other.config_restore(&snapshot).unwrap();
+        assert_eq!(other.config_get("asm.arch").unwrap(), "arm");
+        assert_eq!(other.config_get_u64("asm.bits").unwrap(), 16);
+    }
+}
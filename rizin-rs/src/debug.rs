@@ -0,0 +1,377 @@
+use crate::error::{Error, Result, with_log};
+use crate::reg::{RegRole, Register, RegisterFile};
+use crate::util::{RzList, cstr_to_string};
+use crate::{Perm, RzCore};
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::path::Path;
+use std::ptr::null;
+
+/// Why the debuggee stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Breakpoint { addr: u64 },
+    Step,
+    Signal(i32),
+    Exited,
+    Other(rizin_sys::RzDebugReasonType),
+}
+
+/// Memory region of the debuggee.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugMap {
+    pub from: u64,
+    /// Exclusive end address.
+    pub to: u64,
+    pub perm: Perm,
+    pub name: Option<String>,
+    pub file: Option<String>,
+}
+
+impl From<&rizin_sys::RzDebugMap> for DebugMap {
+    fn from(m: &rizin_sys::RzDebugMap) -> Self {
+        Self {
+            from: m.addr,
+            to: m.addr_end,
+            perm: Perm(m.perm as u32),
+            name: cstr_to_string(m.name),
+            file: cstr_to_string(m.file),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugThread {
+    pub tid: i32,
+    /// Process state letter, as in `ps`: `s`topped, `r`unning, ...
+    pub status: char,
+    pub pc: u64,
+    pub path: Option<String>,
+}
+
+const SIGKILL: i32 = 9;
+
+/// Process spawned under rizin's native (ptrace) debugger. It is killed on drop.
+/// ptrace only accepts requests from the thread that attached, so it stays on it.
+pub struct Debugger<'a> {
+    core: &'a RzCore,
+    fd: i32,
+    marker: PhantomData<*const ()>,
+}
+
+impl Drop for Debugger<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            let dbg = self.dbg();
+            if !rizin_sys::rz_debug_is_dead(dbg) {
+                rizin_sys::rz_debug_kill(dbg, (*dbg).pid, (*dbg).tid, SIGKILL);
+            }
+            rizin_sys::rz_core_file_close_fd(self.core.0.as_ptr(), self.fd);
+        }
+    }
+}
+
+impl<'a> Debugger<'a> {
+    /// Starts `program` stopped at its first instruction and loads its symbols.
+    /// Fails while the core still debugs another live process, which the
+    /// returned value would otherwise kill and detach on drop.
+    pub fn spawn(core: &'a RzCore, program: impl AsRef<Path>) -> Result<Self> {
+        let dbg = unsafe { core.0.as_ref().dbg };
+        let pid = unsafe { dbg.as_ref() }.map_or(-1, |d| d.pid);
+        if pid >= 0 && !unsafe { rizin_sys::rz_debug_is_dead(dbg) } {
+            return Err(Error::rizin(
+                format!("spawn while debugging pid {}", pid),
+                None,
+            ));
+        }
+        let path = program.as_ref();
+        let spath = path
+            .to_str()
+            .ok_or_else(|| Error::InvalidPath(path.to_owned()))?;
+        let uri = format!("dbg://{}", spath);
+        let curi = CString::new(uri.as_str())?;
+        let (file, log) = with_log(|| unsafe {
+            rizin_sys::rz_core_file_open(core.0.as_ptr(), curi.as_ptr(), Perm::RWX.0 as _, 0)
+        });
+        let fd = unsafe { file.as_ref() }
+            .map(|f| f.fd)
+            .ok_or(Error::OpenFailed { path: uri, log })?;
+        let dbg = Self {
+            core,
+            fd,
+            marker: PhantomData,
+        };
+        let (loaded, log) =
+            with_log(|| unsafe { rizin_sys::rz_core_bin_load(core.0.as_ptr(), null(), 0) });
+        if !loaded {
+            return Err(Error::rizin(format!("load {}", spath), log));
+        }
+        let (ok, log) = with_log(|| unsafe {
+            rizin_sys::rz_core_setup_debugger(core.0.as_ptr(), c"native".as_ptr(), true)
+        });
+        if !ok {
+            return Err(Error::rizin("set up native debugger", log));
+        }
+        Ok(dbg)
+    }
+
+    fn dbg(&self) -> *mut rizin_sys::RzDebug {
+        unsafe { self.core.0.as_ref().dbg }
+    }
+
+    pub fn pid(&self) -> i32 {
+        unsafe { (*self.dbg()).pid }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        unsafe { rizin_sys::rz_debug_is_dead(self.dbg()) }
+    }
+
+    fn stop_reason(&self) -> StopReason {
+        if self.is_dead() {
+            return StopReason::Exited;
+        }
+        let reason = unsafe { &(*self.dbg()).reason };
+        match reason.type_ {
+            rizin_sys::RZ_DEBUG_REASON_DEAD => StopReason::Exited,
+            rizin_sys::RZ_DEBUG_REASON_BREAKPOINT => StopReason::Breakpoint {
+                addr: reason.bp_addr,
+            },
+            rizin_sys::RZ_DEBUG_REASON_STEP => StopReason::Step,
+            rizin_sys::RZ_DEBUG_REASON_SIGNAL => StopReason::Signal(reason.signum),
+            t => StopReason::Other(t),
+        }
+    }
+
+    /// Resumes until a breakpoint, a signal or exit.
+    pub fn cont(&mut self) -> Result<StopReason> {
+        let (ret, log) = with_log(|| unsafe { rizin_sys::rz_debug_continue(self.dbg()) });
+        if ret < 0 {
+            return Err(Error::rizin("continue", log));
+        }
+        Ok(self.stop_reason())
+    }
+
+    pub fn step(&mut self) -> Result<StopReason> {
+        let (steps, log) = with_log(|| unsafe { rizin_sys::rz_debug_step(self.dbg(), 1) });
+        if steps < 1 {
+            return Err(Error::rizin("step", log));
+        }
+        Ok(self.stop_reason())
+    }
+
+    /// Steps one instruction, running calls to completion.
+    pub fn step_over(&mut self) -> Result<StopReason> {
+        let (steps, log) = with_log(|| unsafe { rizin_sys::rz_debug_step_over(self.dbg(), 1) });
+        if steps < 1 {
+            return Err(Error::rizin("step over", log));
+        }
+        Ok(self.stop_reason())
+    }
+
+    pub fn add_breakpoint(&mut self, addr: u64) -> Result<()> {
+        let (bp, log) = with_log(|| unsafe {
+            rizin_sys::rz_bp_add_sw((*self.dbg()).bp, addr, 1, Perm::X.0 as _)
+        });
+        if bp.is_null() {
+            Err(Error::rizin(format!("breakpoint at {:#x}", addr), log))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn remove_breakpoint(&mut self, addr: u64) -> Result<()> {
+        if unsafe { rizin_sys::rz_bp_del((*self.dbg()).bp, addr) } {
+            Ok(())
+        } else {
+            Err(Error::rizin(
+                format!("remove breakpoint at {:#x}", addr),
+                None,
+            ))
+        }
+    }
+
+    /// Register file refreshed from the stopped thread.
+    fn sync_regs(&mut self) -> Result<RegisterFile<'_>> {
+        let dbg = self.dbg();
+        if !unsafe { rizin_sys::rz_debug_reg_sync(dbg, rizin_sys::RZ_REG_TYPE_ANY as _, 0) } {
+            return Err(Error::rizin("read registers", None));
+        }
+        RegisterFile::from_raw(unsafe { (*dbg).reg })
+            .ok_or(Error::NullPointer("debug register file"))
+    }
+
+    /// Values of the stopped thread's registers, truncated to 64 bits. Use
+    /// [`Debugger::set_reg`] to change them.
+    pub fn regs(&mut self) -> Result<Vec<(Register, u64)>> {
+        let regs = self.sync_regs()?;
+        regs.registers()
+            .into_iter()
+            .map(|r| {
+                let value = regs.get(&r.name)?;
+                Ok((r, value))
+            })
+            .collect()
+    }
+
+    pub fn reg(&mut self, name: &str) -> Result<u64> {
+        self.sync_regs()?.get(name)
+    }
+
+    pub fn set_reg(&mut self, name: &str, value: u64) -> Result<()> {
+        self.sync_regs()?.set(name, value)?;
+        if unsafe { rizin_sys::rz_debug_reg_sync(self.dbg(), rizin_sys::RZ_REG_TYPE_ANY as _, 1) } {
+            Ok(())
+        } else {
+            Err(Error::rizin(format!("write register {}", name), None))
+        }
+    }
+
+    pub fn pc(&mut self) -> Result<u64> {
+        self.sync_regs()?.get_role(RegRole::Pc)
+    }
+
+    /// Reads the debuggee's memory, bypassing the file maps.
+    pub fn read_mem(&self, addr: u64, len: usize) -> Result<Vec<u8>> {
+        let mut buf = vec![0u8; len];
+        let n = unsafe {
+            rizin_sys::rz_io_fd_read_at(self.core.io(), self.fd, addr, buf.as_mut_ptr(), len as _)
+        };
+        if n < 0 {
+            return Err(Error::ReadFailed { addr });
+        }
+        buf.truncate(n as usize);
+        Ok(buf)
+    }
+
+    pub fn write_mem(&self, addr: u64, bytes: &[u8]) -> Result<()> {
+        let (n, log) = with_log(|| unsafe {
+            rizin_sys::rz_io_fd_write_at(
+                self.core.io(),
+                self.fd,
+                addr,
+                bytes.as_ptr(),
+                bytes.len() as _,
+            )
+        });
+        if n as usize == bytes.len() {
+            Ok(())
+        } else {
+            Err(Error::rizin(format!("write at {:#x}", addr), log))
+        }
+    }
+
+    pub fn maps(&self) -> Vec<DebugMap> {
+        let dbg = self.dbg();
+        unsafe { rizin_sys::rz_debug_map_sync(dbg) };
+        RzList::<rizin_sys::RzDebugMap>::from_raw(unsafe {
+            rizin_sys::rz_debug_map_list(dbg, false)
+        })
+        .map(ManuallyDrop::new)
+        .map(|maps| maps.iter().map(DebugMap::from).collect())
+        .unwrap_or_default()
+    }
+
+    pub fn threads(&self) -> Vec<DebugThread> {
+        let dbg = self.dbg();
+        let Some(threads) = (unsafe { (*dbg).cur.as_ref() }).and_then(|p| p.threads) else {
+            return Vec::new();
+        };
+        RzList::<rizin_sys::RzDebugPid>::from_raw(unsafe { threads(dbg, (*dbg).pid) })
+            .map(|list| {
+                list.iter()
+                    .map(|t| DebugThread {
+                        tid: t.pid,
+                        status: t.status as u8 as char,
+                        pc: t.pc,
+                        path: cstr_to_string(t.path),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(all(test, target_os = "linux", target_arch = "x86_64"))]
+mod tests {
+    use crate::RzCore;
+    use crate::debug::{Debugger, StopReason};
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::{self, Command};
+
+    const PROGRAM: &str = r#"
+int counter = 0;
+__attribute__((noinline)) void tick(void) { counter++; }
+int main(void) {
+    for (int i = 0; i < 3; i++)
+        tick();
+    return 0;
+}
+"#;
+
+    /// Build dir of the test program, removed even when the test fails.
+    struct TempDir(PathBuf);
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_debugger() {
+        let dir = TempDir(std::env::temp_dir().join(format!("rizin-rs-debug-{}", process::id())));
+        fs::create_dir_all(&dir.0).unwrap();
+        let src = dir.0.join("tick.c");
+        let exe = dir.0.join("tick");
+        fs::write(&src, PROGRAM).unwrap();
+        let status = Command::new("cc")
+            .args(["-O0", "-g", "-no-pie", "-o"])
+            .arg(&exe)
+            .arg(&src)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let core = RzCore::new();
+        let mut dbg = Debugger::spawn(&core, &exe).unwrap();
+        assert!(dbg.pid() > 0);
+        assert!(Debugger::spawn(&core, &exe).is_err());
+        let tick = core.flag_by_name("sym.tick").unwrap().offset;
+        let counter = core.flag_by_name("obj.counter").unwrap().offset;
+
+        dbg.add_breakpoint(tick).unwrap();
+        assert_eq!(dbg.cont().unwrap(), StopReason::Breakpoint { addr: tick });
+        assert_eq!(dbg.pc().unwrap(), tick);
+        assert!(
+            dbg.regs()
+                .unwrap()
+                .iter()
+                .any(|(r, v)| r.name == "rip" && *v == tick)
+        );
+        assert_eq!(dbg.read_mem(counter, 4).unwrap(), [0, 0, 0, 0]);
+        dbg.write_mem(counter, &[40, 0, 0, 0]).unwrap();
+        assert_eq!(dbg.read_mem(counter, 4).unwrap(), [40, 0, 0, 0]);
+
+        dbg.set_reg("rax", 0x42).unwrap();
+        assert_eq!(dbg.reg("rax").unwrap(), 0x42);
+        assert_eq!(dbg.step().unwrap(), StopReason::Step);
+        assert_ne!(dbg.pc().unwrap(), tick);
+
+        let exe_name = exe.to_str().unwrap();
+        assert!(
+            dbg.maps()
+                .iter()
+                .any(|m| m.file.as_deref() == Some(exe_name))
+        );
+        assert!(dbg.threads().iter().any(|t| t.tid == dbg.pid()));
+
+        assert_eq!(dbg.cont().unwrap(), StopReason::Breakpoint { addr: tick });
+        assert_eq!(dbg.read_mem(counter, 4).unwrap(), [41, 0, 0, 0]);
+        dbg.remove_breakpoint(tick).unwrap();
+        assert_eq!(dbg.cont().unwrap(), StopReason::Exited);
+        assert!(dbg.is_dead());
+    }
+}
@@ -0,0 +1,230 @@
+use crate::RzCore;
+use crate::op::OpType;
+use std::borrow::Cow;
+
+/// Bytes handed to the decoder per instruction when reading from IO.
+const MAX_OP_SIZE: usize = 32;
+
+/// What to do when the bytes at the current address do not decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipPolicy {
+    /// End the iteration.
+    Stop,
+    /// Silently advance by the given number of bytes.
+    Skip(usize),
+    /// Yield an `invalid` instruction covering the given number of bytes.
+    Emit(usize),
+}
+
+impl Default for SkipPolicy {
+    fn default() -> Self {
+        SkipPolicy::Emit(1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub addr: u64,
+    pub size: usize,
+    pub bytes: Vec<u8>,
+    pub mnemonic: String,
+    pub op_type: OpType,
+    pub jump: Option<u64>,
+    pub fail: Option<u64>,
+    pub ptr: Option<u64>,
+    pub val: Option<u64>,
+}
+
+impl Instruction {
+    pub fn is_invalid(&self) -> bool {
+        self.op_type == OpType::Ill
+    }
+
+    fn invalid(addr: u64, bytes: &[u8]) -> Self {
+        Self {
+            addr,
+            size: bytes.len(),
+            bytes: bytes.to_vec(),
+            mnemonic: "invalid".to_owned(),
+            op_type: OpType::Ill,
+            jump: None,
+            fail: None,
+            ptr: None,
+            val: None,
+        }
+    }
+}
+
+enum Source<'a> {
+    Bytes(&'a [u8]),
+    Io,
+}
+
+/// Iterator over consecutive instructions, see [`RzCore::disassemble`].
+pub struct Disassembly<'a> {
+    core: &'a RzCore,
+    source: Source<'a>,
+    base: u64,
+    addr: u64,
+    end: u64,
+    policy: SkipPolicy,
+}
+
+impl<'a> Disassembly<'a> {
+    pub fn skip_policy(mut self, policy: SkipPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Bytes at the current address, or `None` if IO failed to read them.
+    fn window(&self) -> Option<Cow<'a, [u8]>> {
+        let remaining = (self.end - self.addr) as usize;
+        match self.source {
+            Source::Bytes(bytes) => Some(Cow::Borrowed(&bytes[(self.addr - self.base) as usize..])),
+            Source::Io => self
+                .core
+                .read_at(self.addr, remaining.min(MAX_OP_SIZE))
+                .ok()
+                .map(Cow::Owned),
+        }
+    }
+}
+
+impl Iterator for Disassembly<'_> {
+    type Item = Instruction;
+
+    fn next(&mut self) -> Option<Instruction> {
+        while self.addr < self.end {
+            let Some(bytes) = self.window() else {
+                self.addr = self.end;
+                return None;
+            };
+            let op = self
+                .core
+                .analysis_op(
+                    &bytes,
+                    self.addr as usize,
+                    rizin_sys::RZ_ANALYSIS_OP_MASK_DISASM,
+                )
+                .ok()
+                .filter(|op| {
+                    op.size() > 0 && op.size() <= bytes.len() && op.op_type() != OpType::Ill
+                });
+            let Some(op) = op else {
+                match self.policy {
+                    SkipPolicy::Stop => {
+                        self.addr = self.end;
+                        return None;
+                    }
+                    SkipPolicy::Skip(n) => {
+                        self.addr += n.clamp(1, bytes.len()) as u64;
+                        continue;
+                    }
+                    SkipPolicy::Emit(n) => {
+                        let insn =
+                            Instruction::invalid(self.addr, &bytes[..n.clamp(1, bytes.len())]);
+                        self.addr += insn.size as u64;
+                        return Some(insn);
+                    }
+                }
+            };
+            let size = op.size();
+            let insn = Instruction {
+                addr: self.addr,
+                size,
+                bytes: bytes[..size].to_vec(),
+                mnemonic: op.mnemonic().unwrap_or("invalid").to_owned(),
+                op_type: op.op_type(),
+                jump: op.jump(),
+                fail: op.fail(),
+                ptr: op.ptr(),
+                val: op.val(),
+            };
+            self.addr += size as u64;
+            return Some(insn);
+        }
+        None
+    }
+}
+
+impl RzCore {
+    /// Disassembles `bytes` as if mapped at `base_addr`.
+    pub fn disassemble<'a>(&'a self, bytes: &'a [u8], base_addr: u64) -> Disassembly<'a> {
+        Disassembly {
+            core: self,
+            source: Source::Bytes(bytes),
+            base: base_addr,
+            addr: base_addr,
+            end: base_addr + bytes.len() as u64,
+            policy: SkipPolicy::default(),
+        }
+    }
+
+    /// Disassembles `[start, end)` reading from the core's IO. Iteration ends at
+    /// the first address that fails to read.
+    pub fn disassemble_range(&self, start: u64, end: u64) -> Disassembly<'_> {
+        Disassembly {
+            core: self,
+            source: Source::Io,
+            base: start,
+            addr: start,
+            end,
+            policy: SkipPolicy::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::disasm::SkipPolicy;
+    use crate::{Perm, RzCore};
+
+    #[test]
+    fn test_disassemble() {
+        let core = RzCore::new();
+        core.config_set("asm.arch", "x86").unwrap();
+        core.config_set("asm.bits", "64").unwrap();
+        let code = [0x90, 0xd6, 0xeb, 0x00, 0xc3];
+
+        let insns: Vec<_> = core.disassemble(&code, 0x1000).collect();
+        let addrs: Vec<_> = insns.iter().map(|i| i.addr).collect();
+        assert_eq!(addrs, vec![0x1000, 0x1001, 0x1002, 0x1004]);
+        assert_eq!(insns[0].mnemonic, "nop");
+        assert!(insns[1].is_invalid());
+        assert_eq!(insns[2].jump, Some(0x1004));
+        assert_eq!(insns[3].bytes, vec![0xc3]);
+
+        let skipped = core
+            .disassemble(&code, 0x1000)
+            .skip_policy(SkipPolicy::Skip(1))
+            .count();
+        assert_eq!(skipped, 3);
+
+        let stopped = core
+            .disassemble(&code, 0x1000)
+            .skip_policy(SkipPolicy::Stop)
+            .count();
+        assert_eq!(stopped, 1);
+    }
+
+    #[test]
+    fn test_disassemble_range() {
+        let core = RzCore::new();
+        core.config_set("asm.arch", "x86").unwrap();
+        core.config_set("asm.bits", "64").unwrap();
+        let _file = core.open("malloc://0x100", Perm::RWX, 0x1000).unwrap();
+        core.write_at(0x1000, &[0x90, 0xeb, 0x00, 0xc3]).unwrap();
+
+        let insns: Vec<_> = core.disassemble_range(0x1000, 0x1004).collect();
+        let addrs: Vec<_> = insns.iter().map(|i| i.addr).collect();
+        assert_eq!(addrs, vec![0x1000, 0x1001, 0x1003]);
+        assert_eq!(insns[0].mnemonic, "nop");
+        assert_eq!(insns[1].jump, Some(0x1003));
+        assert_eq!(insns[2].bytes, vec![0xc3]);
+
+        // The last instruction is cut off by the end of the range.
+        let insns: Vec<_> = core.disassemble_range(0x1000, 0x1002).collect();
+        assert_eq!(insns.len(), 2);
+        assert!(insns[1].is_invalid());
+    }
+}
@@ -0,0 +1,218 @@
+use crate::asm::AsmError;
+use crate::config::ConfigKind;
+use crate::project::ProjectError;
+use std::cell::RefCell;
+use std::ffi::{NulError, c_char};
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str::Utf8Error;
+use std::sync::Once;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors returned by the safe API. `log` holds the last warning or error rizin
+/// logged while the failing call ran, if any.
+#[derive(Debug)]
+pub enum Error {
+    NulInString(NulError),
+    InvalidUtf8(Utf8Error),
+    InvalidPath(PathBuf),
+    NullPointer(&'static str),
+    UnknownConfigKey(String),
+    /// A typed config accessor was used on a node of another kind.
+    ConfigKindMismatch {
+        key: String,
+        kind: ConfigKind,
+    },
+    UnknownRegister(String),
+    UnknownFlag(String),
+    DecodeFailed {
+        addr: u64,
+        log: Option<String>,
+    },
+    OpenFailed {
+        path: String,
+        log: Option<String>,
+    },
+    AnalysisFailed {
+        addr: Option<u64>,
+        log: Option<String>,
+    },
+    AssembleFailed {
+        addr: u64,
+        line: Option<AsmError>,
+        log: Option<String>,
+    },
+    IlUnsupported,
+    /// The IL variable `name` does not hold `bits` wide bitvectors.
+    IlSortMismatch {
+        name: String,
+        bits: u32,
+    },
+    IlStepFailed {
+        pc: u64,
+        code: u32,
+    },
+    ReadFailed {
+        addr: u64,
+    },
+    CommandFailed {
+        cmd: String,
+        log: Option<String>,
+    },
+    /// Project load or save failed; `details` holds migration messages.
+    ProjectFailed {
+        path: PathBuf,
+        kind: ProjectError,
+        details: Vec<String>,
+    },
+    Io(io::Error),
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+    /// Any other call rizin reported as failed.
+    Rizin {
+        op: String,
+        log: Option<String>,
+    },
+}
+
+impl Error {
+    pub(crate) fn rizin(op: impl Into<String>, log: Option<String>) -> Self {
+        Error::Rizin { op: op.into(), log }
+    }
+
+    /// Message rizin logged for this failure.
+    pub fn log(&self) -> Option<&str> {
+        match self {
+            Error::DecodeFailed { log, .. }
+            | Error::OpenFailed { log, .. }
+            | Error::AnalysisFailed { log, .. }
+            | Error::AssembleFailed { log, .. }
+            | Error::CommandFailed { log, .. }
+            | Error::Rizin { log, .. } => log.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NulInString(e) => write!(f, "string contains nul byte: {}", e),
+            Error::InvalidUtf8(e) => write!(f, "invalid utf8: {}", e),
+            Error::InvalidPath(p) => write!(f, "invalid path {}", p.display()),
+            Error::NullPointer(what) => write!(f, "{} is null", what),
+            Error::UnknownConfigKey(k) => write!(f, "unknown config key {}", k),
+            Error::ConfigKindMismatch { key, kind } => {
+                write!(f, "config key {} holds {:?}", key, kind)
+            }
+            Error::UnknownRegister(r) => write!(f, "unknown register {}", r),
+            Error::UnknownFlag(name) => write!(f, "unknown flag {}", name),
+            Error::DecodeFailed { addr, .. } => write!(f, "failed decode at {:#x}", addr),
+            Error::OpenFailed { path, .. } => write!(f, "failed open {}", path),
+            Error::AnalysisFailed {
+                addr: Some(addr), ..
+            } => {
+                write!(f, "failed analysis at {:#x}", addr)
+            }
+            Error::AnalysisFailed { addr: None, .. } => write!(f, "failed analysis"),
+            Error::AssembleFailed {
+                line: Some(line), ..
+            } => write!(f, "failed assemble {}", line),
+            Error::AssembleFailed { addr, .. } => write!(f, "failed assemble at {:#x}", addr),
+            Error::IlUnsupported => write!(f, "arch has no il support"),
+            Error::IlSortMismatch { name, bits } => {
+                write!(f, "il variable {} does not hold {} bits", name, bits)
+            }
+            Error::IlStepFailed { pc, code } => write!(f, "failed il step at {:#x}: {}", pc, code),
+            Error::ReadFailed { addr } => write!(f, "failed read at {:#x}", addr),
+            Error::CommandFailed { cmd, .. } => write!(f, "failed command `{}`", cmd),
+            Error::ProjectFailed {
+                path,
+                kind,
+                details,
+            } => {
+                write!(f, "failed project {}: ", path.display())?;
+                match kind.message() {
+                    Some(msg) => write!(f, "{}", msg),
+                    None => write!(f, "{:?}", kind),
+                }?;
+                details.iter().try_for_each(|d| write!(f, "; {}", d))
+            }
+            Error::Io(e) => write!(f, "io: {}", e),
+            #[cfg(feature = "serde")]
+            Error::Json(e) => write!(f, "json: {}", e),
+            Error::Rizin { op, .. } => write!(f, "failed {}", op),
+        }?;
+        match self.log() {
+            Some(log) => write!(f, " ({})", log),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::NulInString(e) => Some(e),
+            Error::InvalidUtf8(e) => Some(e),
+            Error::Io(e) => Some(e),
+            #[cfg(feature = "serde")]
+            Error::Json(e) => Some(e),
+            Error::AssembleFailed { line: Some(e), .. } => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<NulError> for Error {
+    fn from(e: NulError) -> Self {
+        Error::NulInString(e)
+    }
+}
+
+impl From<Utf8Error> for Error {
+    fn from(e: Utf8Error) -> Self {
+        Error::InvalidUtf8(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+thread_local! {
+    static LAST_LOG: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Records warnings and errors so they can be attached to the failing call.
+unsafe extern "C" fn log_sink(output: *const c_char, level: rizin_sys::RzLogLevel) {
+    if level < rizin_sys::RZ_LOGLVL_WARN {
+        return;
+    }
+    if let Some(msg) = crate::util::cstr_to_string(output) {
+        LAST_LOG.with(|l| *l.borrow_mut() = Some(msg.trim_end().to_owned()));
+    }
+}
+
+pub(crate) fn install_log_hook() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| unsafe { rizin_sys::rizin_sys_set_log_sink(Some(log_sink)) });
+}
+
+/// Runs `f` and returns what rizin logged meanwhile on this thread.
+pub(crate) fn with_log<R>(f: impl FnOnce() -> R) -> (R, Option<String>) {
+    LAST_LOG.with(|l| l.borrow_mut().take());
+    let res = f();
+    (res, LAST_LOG.with(|l| l.borrow_mut().take()))
+}
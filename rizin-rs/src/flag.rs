@@ -0,0 +1,215 @@
+use crate::RzCore;
+use crate::error::{Error, Result, with_log};
+use crate::util::{RzList, cstr_to_string};
+use std::ffi::{CString, c_void};
+use std::mem::ManuallyDrop;
+use std::ptr::{addr_of_mut, null};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Flag {
+    pub name: String,
+    /// Name before escaping for the rizin shell.
+    pub realname: String,
+    pub offset: u64,
+    pub size: u64,
+    pub space: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl From<&rizin_sys::RzFlagItem> for Flag {
+    fn from(f: &rizin_sys::RzFlagItem) -> Self {
+        let name = cstr_to_string(f.name).unwrap_or_default();
+        Self {
+            realname: cstr_to_string(f.realname).unwrap_or_else(|| name.clone()),
+            name,
+            offset: f.offset,
+            size: f.size,
+            space: unsafe { f.space.as_ref() }.and_then(|s| cstr_to_string(s.name)),
+            comment: cstr_to_string(f.comment),
+        }
+    }
+}
+
+unsafe extern "C" fn collect_flag(fi: *mut rizin_sys::RzFlagItem, user: *mut c_void) -> bool {
+    let flags = unsafe { &mut *(user as *mut Vec<Flag>) };
+    if let Some(fi) = unsafe { fi.as_ref() } {
+        flags.push(Flag::from(fi));
+    }
+    true
+}
+
+impl RzCore {
+    fn flag(&self) -> *mut rizin_sys::RzFlag {
+        unsafe { self.0.as_ref().flags }
+    }
+
+    fn flag_spaces(&self) -> *mut rizin_sys::RzSpaces {
+        unsafe { addr_of_mut!((*self.flag()).spaces) }
+    }
+
+    fn flag_item(&self, name: &str) -> Result<*mut rizin_sys::RzFlagItem> {
+        let cname = CString::new(name)?;
+        let item = unsafe { rizin_sys::rz_flag_get(self.flag(), cname.as_ptr()) };
+        if item.is_null() {
+            Err(Error::UnknownFlag(name.to_owned()))
+        } else {
+            Ok(item)
+        }
+    }
+
+    pub fn flags(&self) -> Vec<Flag> {
+        let mut flags = Vec::new();
+        unsafe {
+            rizin_sys::rz_flag_foreach(
+                self.flag(),
+                Some(collect_flag),
+                addr_of_mut!(flags) as *mut c_void,
+            )
+        };
+        flags
+    }
+
+    /// Flags in `space`, empty if the space does not exist.
+    pub fn flags_in_space(&self, space: &str) -> Result<Vec<Flag>> {
+        let cspace = CString::new(space)?;
+        let sp = unsafe { rizin_sys::rz_spaces_get(self.flag_spaces(), cspace.as_ptr()) };
+        let mut flags = Vec::new();
+        if !sp.is_null() {
+            unsafe {
+                rizin_sys::rz_flag_foreach_space(
+                    self.flag(),
+                    sp,
+                    Some(collect_flag),
+                    addr_of_mut!(flags) as *mut c_void,
+                )
+            };
+        }
+        Ok(flags)
+    }
+
+    pub fn flag_by_name(&self, name: &str) -> Option<Flag> {
+        self.flag_item(name)
+            .ok()
+            .and_then(|item| unsafe { item.as_ref() })
+            .map(Flag::from)
+    }
+
+    /// Preferred flag at `addr`, following rizin's priority between spaces.
+    pub fn flag_at(&self, addr: u64) -> Option<Flag> {
+        unsafe { rizin_sys::rz_flag_get_i(self.flag(), addr).as_ref() }.map(Flag::from)
+    }
+
+    pub fn flags_at(&self, addr: u64) -> Vec<Flag> {
+        RzList::<rizin_sys::RzFlagItem>::from_raw(unsafe {
+            rizin_sys::rz_flag_get_list(self.flag(), addr) as *mut _
+        })
+        .map(ManuallyDrop::new)
+        .map(|flags| flags.iter().map(Flag::from).collect())
+        .unwrap_or_default()
+    }
+
+    /// Adds or moves the flag `name` in the current flag space.
+    pub fn add_flag(&self, name: &str, addr: u64, size: u64) -> Result<Flag> {
+        let cname = CString::new(name)?;
+        let (item, log) = with_log(|| unsafe {
+            rizin_sys::rz_flag_set(self.flag(), cname.as_ptr(), addr, size as _)
+        });
+        unsafe { item.as_ref() }
+            .map(Flag::from)
+            .ok_or_else(|| Error::rizin(format!("add flag {}", name), log))
+    }
+
+    pub fn rename_flag(&self, name: &str, new_name: &str) -> Result<()> {
+        let item = self.flag_item(name)?;
+        let cnew = CString::new(new_name)?;
+        let (ok, log) =
+            with_log(|| unsafe { rizin_sys::rz_flag_rename(self.flag(), item, cnew.as_ptr()) });
+        if ok {
+            Ok(())
+        } else {
+            Err(Error::rizin(
+                format!("rename flag {} -> {}", name, new_name),
+                log,
+            ))
+        }
+    }
+
+    pub fn set_flag_comment(&self, name: &str, comment: &str) -> Result<()> {
+        let item = self.flag_item(name)?;
+        let ccomment = CString::new(comment)?;
+        unsafe { rizin_sys::rz_flag_item_set_comment(item, ccomment.as_ptr()) };
+        Ok(())
+    }
+
+    pub fn remove_flag(&self, name: &str) -> Result<()> {
+        let item = self.flag_item(name)?;
+        if unsafe { rizin_sys::rz_flag_unset(self.flag(), item) } {
+            Ok(())
+        } else {
+            Err(Error::rizin(format!("remove flag {}", name), None))
+        }
+    }
+
+    /// Makes `space` current, creating it if needed; undo with [`RzCore::pop_flag_space`].
+    pub fn push_flag_space(&self, space: &str) -> Result<()> {
+        let cspace = CString::new(space)?;
+        if unsafe { rizin_sys::rz_spaces_push(self.flag_spaces(), cspace.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(Error::rizin(format!("push flag space {}", space), None))
+        }
+    }
+
+    pub fn pop_flag_space(&self) -> Result<()> {
+        if unsafe { rizin_sys::rz_spaces_pop(self.flag_spaces()) } {
+            Ok(())
+        } else {
+            Err(Error::rizin("pop flag space", None))
+        }
+    }
+
+    /// Current flag space, `None` when flags go to the global space.
+    pub fn current_flag_space(&self) -> Option<String> {
+        unsafe { (*self.flag_spaces()).current.as_ref() }.and_then(|s| cstr_to_string(s.name))
+    }
+
+    /// Resets to the global flag space without touching the push stack.
+    pub fn unset_flag_space(&self) {
+        unsafe { rizin_sys::rz_spaces_set(self.flag_spaces(), null()) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RzCore;
+
+    #[test]
+    fn test_flags() {
+        let core = RzCore::new();
+        core.push_flag_space("mine").unwrap();
+        assert_eq!(core.current_flag_space().as_deref(), Some("mine"));
+        let flag = core.add_flag("sym.foo", 0x1000, 16).unwrap();
+        assert_eq!(flag.space.as_deref(), Some("mine"));
+        core.pop_flag_space().unwrap();
+        core.add_flag("sym.bar", 0x1000, 4).unwrap();
+
+        assert_eq!(core.flag_by_name("sym.foo").unwrap().offset, 0x1000);
+        assert_eq!(core.flags_at(0x1000).len(), 2);
+        assert!(core.flag_at(0x1000).is_some());
+        let mine = core.flags_in_space("mine").unwrap();
+        assert_eq!(mine.len(), 1);
+        assert_eq!(mine[0].name, "sym.foo");
+
+        core.rename_flag("sym.foo", "sym.baz").unwrap();
+        core.set_flag_comment("sym.baz", "hello").unwrap();
+        assert_eq!(
+            core.flag_by_name("sym.baz").unwrap().comment.as_deref(),
+            Some("hello")
+        );
+        assert!(core.flag_by_name("sym.foo").is_none());
+
+        core.remove_flag("sym.baz").unwrap();
+        assert!(core.remove_flag("sym.baz").is_err());
+        assert_eq!(core.flags_at(0x1000).len(), 1);
+    }
+}
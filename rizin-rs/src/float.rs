@@ -0,0 +1,314 @@
+use crate::bitvector::BitVector;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops;
+use std::ptr::NonNull;
+
+/// Rounding mode used by float arithmetic; operators use `NearestEven`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RoundingMode {
+    #[default]
+    NearestEven,
+    NearestAway,
+    TowardPositive,
+    TowardNegative,
+    TowardZero,
+}
+
+impl From<RoundingMode> for rizin_sys::RzFloatRMode {
+    fn from(value: RoundingMode) -> Self {
+        match value {
+            RoundingMode::NearestEven => rizin_sys::RZ_FLOAT_RMODE_RNE,
+            RoundingMode::NearestAway => rizin_sys::RZ_FLOAT_RMODE_RNA,
+            RoundingMode::TowardPositive => rizin_sys::RZ_FLOAT_RMODE_RTP,
+            RoundingMode::TowardNegative => rizin_sys::RZ_FLOAT_RMODE_RTN,
+            RoundingMode::TowardZero => rizin_sys::RZ_FLOAT_RMODE_RTZ,
+        }
+    }
+}
+
+impl From<rizin_sys::RzFloatRMode> for RoundingMode {
+    fn from(value: rizin_sys::RzFloatRMode) -> Self {
+        match value {
+            rizin_sys::RZ_FLOAT_RMODE_RNA => RoundingMode::NearestAway,
+            rizin_sys::RZ_FLOAT_RMODE_RTP => RoundingMode::TowardPositive,
+            rizin_sys::RZ_FLOAT_RMODE_RTN => RoundingMode::TowardNegative,
+            rizin_sys::RZ_FLOAT_RMODE_RTZ => RoundingMode::TowardZero,
+            _ => RoundingMode::NearestEven,
+        }
+    }
+}
+
+/// IEEE754 float of any rizin format.
+///
+/// # Panics
+///
+/// Arithmetic on floats of different formats panics, through the operators as
+/// well as [`Float::add`] and friends. The `checked_*` methods return `None`
+/// instead.
+pub struct Float(pub(crate) NonNull<rizin_sys::RzFloat>);
+
+unsafe impl Send for Float {}
+unsafe impl Sync for Float {}
+
+impl Drop for Float {
+    fn drop(&mut self) {
+        unsafe {
+            rizin_sys::rz_float_free(self.0.as_ptr());
+        }
+    }
+}
+
+impl Clone for Float {
+    fn clone(&self) -> Self {
+        unsafe { Self::from_raw_dup(self.0.as_ptr()) }.expect("null ptr")
+    }
+}
+
+impl Float {
+    /// Takes ownership of a float allocated by rizin.
+    pub(crate) fn from_raw(f: *mut rizin_sys::RzFloat) -> Option<Self> {
+        NonNull::new(f).map(Self)
+    }
+
+    /// Copies a float still owned by rizin.
+    pub(crate) unsafe fn from_raw_dup(f: *const rizin_sys::RzFloat) -> Option<Self> {
+        if f.is_null() {
+            None
+        } else {
+            Self::from_raw(unsafe { rizin_sys::rz_float_dup(f) })
+        }
+    }
+
+    /// Positive zero in `format`.
+    pub fn new(format: rizin_sys::RzFloatFormat) -> Self {
+        Self::from_raw(unsafe { rizin_sys::rz_float_new(format) }).expect("null ptr")
+    }
+
+    pub fn from_f32(value: f32) -> Self {
+        Self::from_raw(unsafe { rizin_sys::rz_float_new_from_f32(value) }).expect("null ptr")
+    }
+
+    pub fn from_f64(value: f64) -> Self {
+        Self::from_raw(unsafe { rizin_sys::rz_float_new_from_f64(value) }).expect("null ptr")
+    }
+
+    /// Reinterprets the bits of `bv` as an IEEE754 binary float of the same width.
+    pub fn from_bits(bv: &BitVector) -> Option<Self> {
+        Self::from_raw(unsafe { rizin_sys::rz_float_new_from_bv(bv.as_ptr()) })
+    }
+
+    pub fn format(&self) -> rizin_sys::RzFloatFormat {
+        unsafe { self.0.as_ref().r }
+    }
+
+    pub fn bits(&self) -> BitVector {
+        unsafe { BitVector::from_raw_dup(self.0.as_ref().s) }.expect("null ptr")
+    }
+
+    /// Value as an `f64` for 32 and 64 bit formats.
+    pub fn to_f64(&self) -> Option<f64> {
+        match self.format() {
+            rizin_sys::RZ_FLOAT_IEEE754_BIN_32 => {
+                Some(f32::from_bits(self.bits().to_u64() as u32) as f64)
+            }
+            rizin_sys::RZ_FLOAT_IEEE754_BIN_64 => Some(f64::from_bits(self.bits().to_u64())),
+            _ => None,
+        }
+    }
+
+    pub fn is_nan(&self) -> bool {
+        unsafe { rizin_sys::rz_float_is_nan(self.0.as_ptr()) }
+    }
+
+    pub fn is_inf(&self) -> bool {
+        unsafe { rizin_sys::rz_float_is_inf(self.0.as_ptr()) }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        unsafe { rizin_sys::rz_float_is_zero(self.0.as_ptr()) }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        unsafe { rizin_sys::rz_float_is_negative(self.0.as_ptr()) }
+    }
+
+    pub fn add(&self, other: &Self, mode: RoundingMode) -> Self {
+        self.checked_add(other, mode)
+            .expect("float format mismatch")
+    }
+
+    pub fn checked_add(&self, other: &Self, mode: RoundingMode) -> Option<Self> {
+        Self::from_raw(unsafe {
+            rizin_sys::rz_float_add(self.0.as_ptr(), other.0.as_ptr(), mode.into())
+        })
+    }
+
+    pub fn sub(&self, other: &Self, mode: RoundingMode) -> Self {
+        self.checked_sub(other, mode)
+            .expect("float format mismatch")
+    }
+
+    pub fn checked_sub(&self, other: &Self, mode: RoundingMode) -> Option<Self> {
+        Self::from_raw(unsafe {
+            rizin_sys::rz_float_sub(self.0.as_ptr(), other.0.as_ptr(), mode.into())
+        })
+    }
+
+    pub fn mul(&self, other: &Self, mode: RoundingMode) -> Self {
+        self.checked_mul(other, mode)
+            .expect("float format mismatch")
+    }
+
+    pub fn checked_mul(&self, other: &Self, mode: RoundingMode) -> Option<Self> {
+        Self::from_raw(unsafe {
+            rizin_sys::rz_float_mul(self.0.as_ptr(), other.0.as_ptr(), mode.into())
+        })
+    }
+
+    pub fn div(&self, other: &Self, mode: RoundingMode) -> Self {
+        self.checked_div(other, mode)
+            .expect("float format mismatch")
+    }
+
+    pub fn checked_div(&self, other: &Self, mode: RoundingMode) -> Option<Self> {
+        Self::from_raw(unsafe {
+            rizin_sys::rz_float_div(self.0.as_ptr(), other.0.as_ptr(), mode.into())
+        })
+    }
+
+    pub fn rem(&self, other: &Self, mode: RoundingMode) -> Self {
+        self.checked_rem(other, mode)
+            .expect("float format mismatch")
+    }
+
+    pub fn checked_rem(&self, other: &Self, mode: RoundingMode) -> Option<Self> {
+        Self::from_raw(unsafe {
+            rizin_sys::rz_float_mod(self.0.as_ptr(), other.0.as_ptr(), mode.into())
+        })
+    }
+
+    pub fn sqrt(&self, mode: RoundingMode) -> Self {
+        Self::from_raw(unsafe { rizin_sys::rz_float_sqrt(self.0.as_ptr(), mode.into()) })
+            .expect("null ptr")
+    }
+}
+
+impl PartialEq for Float {
+    /// IEEE754 equality, so NaN is never equal to itself.
+    fn eq(&self, other: &Self) -> bool {
+        !self.is_nan()
+            && !other.is_nan()
+            && unsafe { rizin_sys::rz_float_cmp(self.0.as_ptr(), other.0.as_ptr()) } == 0
+    }
+}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.is_nan() || other.is_nan() {
+            return None;
+        }
+        Some(unsafe { rizin_sys::rz_float_cmp(self.0.as_ptr(), other.0.as_ptr()) }.cmp(&0))
+    }
+}
+
+macro_rules! float_binop {
+    ($trait:ident, $method:ident) => {
+        impl ops::$trait<&Float> for &Float {
+            type Output = Float;
+
+            fn $method(self, rhs: &Float) -> Float {
+                Float::$method(self, rhs, RoundingMode::NearestEven)
+            }
+        }
+
+        impl ops::$trait for Float {
+            type Output = Float;
+
+            fn $method(self, rhs: Float) -> Float {
+                Float::$method(&self, &rhs, RoundingMode::NearestEven)
+            }
+        }
+    };
+}
+
+float_binop!(Add, add);
+float_binop!(Sub, sub);
+float_binop!(Mul, mul);
+float_binop!(Div, div);
+float_binop!(Rem, rem);
+
+impl ops::Neg for &Float {
+    type Output = Float;
+
+    fn neg(self) -> Float {
+        Float::from_raw(unsafe { rizin_sys::rz_float_neg(self.0.as_ptr()) }).expect("null ptr")
+    }
+}
+
+impl ops::Neg for Float {
+    type Output = Float;
+
+    fn neg(self) -> Float {
+        -&self
+    }
+}
+
+impl From<f32> for Float {
+    fn from(value: f32) -> Self {
+        Self::from_f32(value)
+    }
+}
+
+impl From<f64> for Float {
+    fn from(value: f64) -> Self {
+        Self::from_f64(value)
+    }
+}
+
+impl fmt::Display for Float {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_f64() {
+            Some(v) => fmt::Display::fmt(&v, f),
+            None => fmt::LowerHex::fmt(&self.bits(), f),
+        }
+    }
+}
+
+impl fmt::LowerHex for Float {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.bits(), f)
+    }
+}
+
+impl fmt::Debug for Float {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Float")
+            .field("format", &self.format())
+            .field("bits", &self.bits())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::float::{Float, RoundingMode};
+
+    #[test]
+    fn test_float_ops() {
+        let x = Float::from_f64(1.5);
+        let y = Float::from_f64(2.25);
+        assert_eq!((&x + &y).to_f64(), Some(3.75));
+        assert_eq!((&y - &x).to_f64(), Some(0.75));
+        assert_eq!((&x * &y).to_f64(), Some(3.375));
+        assert_eq!((-&x).to_f64(), Some(-1.5));
+        assert!(x < y);
+        assert_eq!(Float::from_bits(&x.bits()).unwrap(), x);
+        assert_eq!(Float::from_f32(0.5).to_f64(), Some(0.5));
+        assert_eq!(x.to_string(), "1.5");
+        assert!(
+            x.checked_add(&Float::from_f32(0.5), RoundingMode::NearestEven)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use crate::RzCore;
+use crate::analysis::{BasicBlock, Function};
+use crate::xref::XRefKind;
+#[cfg(feature = "petgraph")]
+use petgraph::graph::NodeIndex;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    Jump,
+    Fail,
+    /// Switch case taken when the selector equals the value.
+    Case(u64),
+    Call,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub kind: EdgeKind,
+}
+
+/// Control-flow graph of a function. Edges index into `blocks`.
+#[derive(Debug, Clone)]
+pub struct Cfg {
+    pub blocks: Vec<BasicBlock>,
+    pub edges: Vec<Edge>,
+    pub entry: Option<usize>,
+}
+
+impl Cfg {
+    pub fn successors(&self, block: usize) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |e| e.from == block)
+    }
+
+    pub fn predecessors(&self, block: usize) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |e| e.to == block)
+    }
+
+    #[cfg(feature = "petgraph")]
+    pub fn to_graph(&self) -> petgraph::graph::DiGraph<BasicBlock, EdgeKind> {
+        let mut graph =
+            petgraph::graph::DiGraph::with_capacity(self.blocks.len(), self.edges.len());
+        self.blocks.iter().for_each(|bb| {
+            graph.add_node(bb.clone());
+        });
+        self.edges.iter().for_each(|e| {
+            graph.add_edge(NodeIndex::new(e.from), NodeIndex::new(e.to), e.kind);
+        });
+        graph
+    }
+}
+
+impl Function {
+    /// Builds the function's CFG. Call edges only appear for recursive calls,
+    /// since other callees have no block in this graph; see [`RzCore::call_graph`].
+    pub fn cfg(&self) -> Cfg {
+        let blocks = self.blocks.clone();
+        let index: HashMap<u64, usize> = blocks
+            .iter()
+            .enumerate()
+            .map(|(i, bb)| (bb.addr, i))
+            .collect();
+        let mut edges = Vec::new();
+        for (from, bb) in blocks.iter().enumerate() {
+            let mut push = |to: Option<u64>, kind| {
+                if let Some(&to) = to.and_then(|to| index.get(&to)) {
+                    edges.push(Edge { from, to, kind });
+                }
+            };
+            push(bb.jump, EdgeKind::Jump);
+            push(bb.fail, EdgeKind::Fail);
+            bb.switch_cases
+                .iter()
+                .for_each(|c| push(Some(c.jump), EdgeKind::Case(c.value)));
+        }
+        self.xrefs_from
+            .iter()
+            .filter(|x| x.kind == XRefKind::Call)
+            .for_each(|x| {
+                let from = blocks.iter().position(|bb| bb.contains(x.from));
+                if let (Some(from), Some(&to)) = (from, index.get(&x.to)) {
+                    edges.push(Edge {
+                        from,
+                        to,
+                        kind: EdgeKind::Call,
+                    });
+                }
+            });
+        Cfg {
+            entry: index.get(&self.addr).copied(),
+            blocks,
+            edges,
+        }
+    }
+}
+
+/// Call graph over analyzed functions. Edges index into `functions`.
+#[derive(Debug, Clone)]
+pub struct CallGraph {
+    pub functions: Vec<Function>,
+    pub edges: Vec<Edge>,
+}
+
+impl CallGraph {
+    #[cfg(feature = "petgraph")]
+    pub fn to_graph(&self) -> petgraph::graph::DiGraph<Function, EdgeKind> {
+        let mut graph =
+            petgraph::graph::DiGraph::with_capacity(self.functions.len(), self.edges.len());
+        self.functions.iter().for_each(|f| {
+            graph.add_node(f.clone());
+        });
+        self.edges.iter().for_each(|e| {
+            graph.add_edge(NodeIndex::new(e.from), NodeIndex::new(e.to), e.kind);
+        });
+        graph
+    }
+}
+
+impl RzCore {
+    pub fn call_graph(&self) -> CallGraph {
+        let functions = self.functions();
+        let index: HashMap<u64, usize> = functions
+            .iter()
+            .enumerate()
+            .map(|(i, f)| (f.addr, i))
+            .collect();
+        let edges = functions
+            .iter()
+            .enumerate()
+            .flat_map(|(from, f)| {
+                let mut callees: Vec<usize> = f
+                    .xrefs_from
+                    .iter()
+                    .filter(|x| x.kind == XRefKind::Call)
+                    .filter_map(|x| index.get(&x.to).copied())
+                    .collect();
+                callees.sort_unstable();
+                callees.dedup();
+                callees.into_iter().map(move |to| Edge {
+                    from,
+                    to,
+                    kind: EdgeKind::Call,
+                })
+            })
+            .collect();
+        CallGraph { functions, edges }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RzCore;
+    use crate::graph::{Edge, EdgeKind};
+    use crate::test_util::load_code;
+
+    #[test]
+    fn test_graphs() {
+        let core = RzCore::new();
+        let _file = load_code(&core);
+        core.analyze_function(0x1000).unwrap();
+        let f = core.function_at(0x1006).unwrap();
+
+        let cfg = f.cfg();
+        let block = |addr| cfg.blocks.iter().position(|bb| bb.addr == addr).unwrap();
+        let (head, inc, ret) = (block(0x1006), block(0x100a), block(0x100b));
+        assert_eq!(cfg.entry, Some(head));
+        let mut succ: Vec<_> = cfg.successors(head).map(|e| (e.to, e.kind)).collect();
+        succ.sort_by_key(|(to, _)| *to);
+        let mut expected = vec![(ret, EdgeKind::Jump), (inc, EdgeKind::Fail)];
+        expected.sort_by_key(|(to, _)| *to);
+        assert_eq!(succ, expected);
+        let mut pred: Vec<_> = cfg.predecessors(ret).map(|e| e.from).collect();
+        pred.sort();
+        let mut expected = vec![head, inc];
+        expected.sort();
+        assert_eq!(pred, expected);
+        assert!(cfg.edges.iter().all(|e| e.kind != EdgeKind::Call));
+
+        let calls = core.call_graph();
+        let func = |addr| calls.functions.iter().position(|f| f.addr == addr).unwrap();
+        assert_eq!(
+            calls.edges,
+            vec![Edge {
+                from: func(0x1000),
+                to: func(0x1006),
+                kind: EdgeKind::Call,
+            }]
+        );
+
+        #[cfg(feature = "petgraph")]
+        {
+            use petgraph::graph::NodeIndex;
+            let graph = cfg.to_graph();
+            assert_eq!(graph.node_count(), 3);
+            assert_eq!(graph.edge_count(), cfg.edges.len());
+            assert_eq!(graph[NodeIndex::new(head)].addr, 0x1006);
+            let graph = calls.to_graph();
+            let edge = graph
+                .find_edge(NodeIndex::new(func(0x1000)), NodeIndex::new(func(0x1006)))
+                .unwrap();
+            assert_eq!(graph[edge], EdgeKind::Call);
+        }
+    }
+}
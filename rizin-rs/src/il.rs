@@ -0,0 +1,982 @@
+use crate::bitvector::BitVector;
+use crate::error::Result;
+use crate::float::RoundingMode;
+use crate::util::cstr_to_string;
+use std::collections::HashSet;
+use std::ffi::{CStr, CString, c_char};
+use std::ptr::null;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+pub type MemIndex = u32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VarKind {
+    Global,
+    Local,
+    LocalPure,
+}
+
+impl From<rizin_sys::RzILVarKind> for VarKind {
+    fn from(value: rizin_sys::RzILVarKind) -> Self {
+        match value {
+            rizin_sys::RZ_IL_VAR_KIND_LOCAL => VarKind::Local,
+            rizin_sys::RZ_IL_VAR_KIND_LOCAL_PURE => VarKind::LocalPure,
+            _ => VarKind::Global,
+        }
+    }
+}
+
+impl From<VarKind> for rizin_sys::RzILVarKind {
+    fn from(value: VarKind) -> Self {
+        match value {
+            VarKind::Global => rizin_sys::RZ_IL_VAR_KIND_GLOBAL,
+            VarKind::Local => rizin_sys::RZ_IL_VAR_KIND_LOCAL,
+            VarKind::LocalPure => rizin_sys::RZ_IL_VAR_KIND_LOCAL_PURE,
+        }
+    }
+}
+
+/// rizin keeps variable and label names by pointer without copying or freeing
+/// them, and ops can outlive whoever built them, so names used in built ops are
+/// kept for the whole process, once per distinct name.
+fn intern(name: &str) -> *const c_char {
+    static NAMES: OnceLock<Mutex<HashSet<&'static CStr>>> = OnceLock::new();
+    let mut names = NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let cname = CString::new(name).expect("il names are checked before building");
+    match names.get(cname.as_c_str()) {
+        Some(interned) => interned.as_ptr(),
+        None => {
+            let interned: &'static CStr = Box::leak(cname.into_boxed_c_str());
+            names.insert(interned);
+            interned.as_ptr()
+        }
+    }
+}
+
+fn check_name(name: &str) -> Result<()> {
+    CString::new(name)?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnOp {
+    /// Boolean negation.
+    Inv,
+    Msb,
+    Lsb,
+    IsZero,
+    Neg,
+    LogNot,
+    Fbits,
+    IsFinite,
+    IsNan,
+    IsInf,
+    IsFzero,
+    IsFneg,
+    IsFpos,
+    Fneg,
+    Fabs,
+    Fsucc,
+    Fpred,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    /// Boolean conjunction.
+    And,
+    Or,
+    Xor,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Sdiv,
+    Mod,
+    Smod,
+    LogAnd,
+    LogOr,
+    LogXor,
+    Eq,
+    Sle,
+    Ule,
+    /// Concatenation, the first operand being the high part.
+    Append,
+    Forder,
+}
+
+/// Rounded float operations taking a single float.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatUnOp {
+    Round,
+    Sqrt,
+    Rsqrt,
+}
+
+/// Rounded float operations taking two floats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatBinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Hypot,
+    Pow,
+}
+
+/// Rounded float operations taking a float and an integer bitvector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatIntOp {
+    Rootn,
+    Pown,
+    Compound,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IlPure {
+    Var {
+        name: String,
+        kind: VarKind,
+    },
+    Ite {
+        cond: Box<IlPure>,
+        then: Box<IlPure>,
+        otherwise: Box<IlPure>,
+    },
+    Let {
+        name: String,
+        exp: Box<IlPure>,
+        body: Box<IlPure>,
+    },
+    Bool(bool),
+    Bitv(BitVector),
+    Unary(UnOp, Box<IlPure>),
+    Binary(BinOp, Box<IlPure>, Box<IlPure>),
+    ShiftRight {
+        fill: Box<IlPure>,
+        x: Box<IlPure>,
+        y: Box<IlPure>,
+    },
+    ShiftLeft {
+        fill: Box<IlPure>,
+        x: Box<IlPure>,
+        y: Box<IlPure>,
+    },
+    Cast {
+        length: u32,
+        fill: Box<IlPure>,
+        val: Box<IlPure>,
+    },
+    Load {
+        mem: MemIndex,
+        key: Box<IlPure>,
+    },
+    LoadW {
+        mem: MemIndex,
+        key: Box<IlPure>,
+        bits: u32,
+    },
+    Float {
+        format: rizin_sys::RzFloatFormat,
+        bv: Box<IlPure>,
+    },
+    FcastInt {
+        signed: bool,
+        length: u32,
+        mode: RoundingMode,
+        f: Box<IlPure>,
+    },
+    FcastFloat {
+        signed: bool,
+        format: rizin_sys::RzFloatFormat,
+        mode: RoundingMode,
+        bv: Box<IlPure>,
+    },
+    Fconvert {
+        format: rizin_sys::RzFloatFormat,
+        mode: RoundingMode,
+        f: Box<IlPure>,
+    },
+    Frequal {
+        x: RoundingMode,
+        y: RoundingMode,
+    },
+    FloatUnary {
+        op: FloatUnOp,
+        mode: RoundingMode,
+        f: Box<IlPure>,
+    },
+    FloatBinary {
+        op: FloatBinOp,
+        mode: RoundingMode,
+        x: Box<IlPure>,
+        y: Box<IlPure>,
+    },
+    Fmad {
+        mode: RoundingMode,
+        x: Box<IlPure>,
+        y: Box<IlPure>,
+        z: Box<IlPure>,
+    },
+    FloatInt {
+        op: FloatIntOp,
+        mode: RoundingMode,
+        f: Box<IlPure>,
+        n: Box<IlPure>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IlEffect {
+    Empty,
+    Nop,
+    Store {
+        mem: MemIndex,
+        key: IlPure,
+        value: IlPure,
+    },
+    StoreW {
+        mem: MemIndex,
+        key: IlPure,
+        value: IlPure,
+    },
+    Set {
+        name: String,
+        is_local: bool,
+        x: IlPure,
+    },
+    Jmp(IlPure),
+    Goto(String),
+    Seq(Box<IlEffect>, Box<IlEffect>),
+    Blk {
+        label: Option<String>,
+        data: Box<IlEffect>,
+        ctrl: Box<IlEffect>,
+    },
+    Repeat {
+        cond: IlPure,
+        body: Box<IlEffect>,
+    },
+    Branch {
+        cond: IlPure,
+        then: Box<IlEffect>,
+        otherwise: Box<IlEffect>,
+    },
+}
+
+impl IlPure {
+    /// Copies the op tree rooted at `op`. Returns `None` for null pointers and unknown opcodes.
+    pub unsafe fn from_raw(op: *const rizin_sys::RzILOpPure) -> Option<Self> {
+        let op = unsafe { op.as_ref()? };
+        let p = |x: *mut rizin_sys::RzILOpPure| unsafe { Self::from_raw(x) }.map(Box::new);
+        let a = &op.op;
+        let un = |op: UnOp, x| Some(IlPure::Unary(op, p(x)?));
+        let bin = |op: BinOp, x, y| Some(IlPure::Binary(op, p(x)?, p(y)?));
+        macro_rules! fun {
+            ($op:expr, $args:expr) => {{
+                let args = $args;
+                Some(IlPure::FloatUnary {
+                    op: $op,
+                    mode: args.rmode.into(),
+                    f: p(args.f)?,
+                })
+            }};
+        }
+        macro_rules! fbin {
+            ($op:expr, $args:expr) => {{
+                let args = $args;
+                Some(IlPure::FloatBinary {
+                    op: $op,
+                    mode: args.rmode.into(),
+                    x: p(args.x)?,
+                    y: p(args.y)?,
+                })
+            }};
+        }
+        macro_rules! fint {
+            ($op:expr, $args:expr) => {{
+                let args = $args;
+                Some(IlPure::FloatInt {
+                    op: $op,
+                    mode: args.rmode.into(),
+                    f: p(args.f)?,
+                    n: p(args.n)?,
+                })
+            }};
+        }
+        macro_rules! fcast_int {
+            ($signed:expr, $args:expr) => {{
+                let args = $args;
+                Some(IlPure::FcastInt {
+                    signed: $signed,
+                    length: args.length,
+                    mode: args.mode.into(),
+                    f: p(args.f)?,
+                })
+            }};
+        }
+        macro_rules! fcast_float {
+            ($signed:expr, $args:expr) => {{
+                let args = $args;
+                Some(IlPure::FcastFloat {
+                    signed: $signed,
+                    format: args.format,
+                    mode: args.mode.into(),
+                    bv: p(args.bv)?,
+                })
+            }};
+        }
+        unsafe {
+            match op.code {
+                rizin_sys::RZ_IL_OP_VAR => Some(IlPure::Var {
+                    name: cstr_to_string(a.var.v)?,
+                    kind: a.var.kind.into(),
+                }),
+                rizin_sys::RZ_IL_OP_ITE => Some(IlPure::Ite {
+                    cond: p(a.ite.condition)?,
+                    then: p(a.ite.x)?,
+                    otherwise: p(a.ite.y)?,
+                }),
+                rizin_sys::RZ_IL_OP_LET => Some(IlPure::Let {
+                    name: cstr_to_string(a.let_.name)?,
+                    exp: p(a.let_.exp)?,
+                    body: p(a.let_.body)?,
+                }),
+                rizin_sys::RZ_IL_OP_B0 => Some(IlPure::Bool(false)),
+                rizin_sys::RZ_IL_OP_B1 => Some(IlPure::Bool(true)),
+                rizin_sys::RZ_IL_OP_INV => un(UnOp::Inv, a.boolinv.x),
+                rizin_sys::RZ_IL_OP_AND => bin(BinOp::And, a.booland.x, a.booland.y),
+                rizin_sys::RZ_IL_OP_OR => bin(BinOp::Or, a.boolor.x, a.boolor.y),
+                rizin_sys::RZ_IL_OP_XOR => bin(BinOp::Xor, a.boolxor.x, a.boolxor.y),
+                rizin_sys::RZ_IL_OP_BITV => BitVector::from_raw_dup(a.bitv.value).map(IlPure::Bitv),
+                rizin_sys::RZ_IL_OP_MSB => un(UnOp::Msb, a.msb.bv),
+                rizin_sys::RZ_IL_OP_LSB => un(UnOp::Lsb, a.lsb.bv),
+                rizin_sys::RZ_IL_OP_IS_ZERO => un(UnOp::IsZero, a.is_zero.bv),
+                rizin_sys::RZ_IL_OP_NEG => un(UnOp::Neg, a.neg.bv),
+                rizin_sys::RZ_IL_OP_LOGNOT => un(UnOp::LogNot, a.lognot.bv),
+                rizin_sys::RZ_IL_OP_ADD => bin(BinOp::Add, a.add.x, a.add.y),
+                rizin_sys::RZ_IL_OP_SUB => bin(BinOp::Sub, a.sub.x, a.sub.y),
+                rizin_sys::RZ_IL_OP_MUL => bin(BinOp::Mul, a.mul.x, a.mul.y),
+                rizin_sys::RZ_IL_OP_DIV => bin(BinOp::Div, a.div.x, a.div.y),
+                rizin_sys::RZ_IL_OP_SDIV => bin(BinOp::Sdiv, a.sdiv.x, a.sdiv.y),
+                rizin_sys::RZ_IL_OP_MOD => bin(BinOp::Mod, a.mod_.x, a.mod_.y),
+                rizin_sys::RZ_IL_OP_SMOD => bin(BinOp::Smod, a.smod.x, a.smod.y),
+                rizin_sys::RZ_IL_OP_LOGAND => bin(BinOp::LogAnd, a.logand.x, a.logand.y),
+                rizin_sys::RZ_IL_OP_LOGOR => bin(BinOp::LogOr, a.logor.x, a.logor.y),
+                rizin_sys::RZ_IL_OP_LOGXOR => bin(BinOp::LogXor, a.logxor.x, a.logxor.y),
+                rizin_sys::RZ_IL_OP_SHIFTR => Some(IlPure::ShiftRight {
+                    fill: p(a.shiftr.fill_bit)?,
+                    x: p(a.shiftr.x)?,
+                    y: p(a.shiftr.y)?,
+                }),
+                rizin_sys::RZ_IL_OP_SHIFTL => Some(IlPure::ShiftLeft {
+                    fill: p(a.shiftl.fill_bit)?,
+                    x: p(a.shiftl.x)?,
+                    y: p(a.shiftl.y)?,
+                }),
+                rizin_sys::RZ_IL_OP_EQ => bin(BinOp::Eq, a.eq.x, a.eq.y),
+                rizin_sys::RZ_IL_OP_SLE => bin(BinOp::Sle, a.sle.x, a.sle.y),
+                rizin_sys::RZ_IL_OP_ULE => bin(BinOp::Ule, a.ule.x, a.ule.y),
+                rizin_sys::RZ_IL_OP_CAST => Some(IlPure::Cast {
+                    length: a.cast.length,
+                    fill: p(a.cast.fill)?,
+                    val: p(a.cast.val)?,
+                }),
+                rizin_sys::RZ_IL_OP_APPEND => bin(BinOp::Append, a.append.high, a.append.low),
+                rizin_sys::RZ_IL_OP_LOAD => Some(IlPure::Load {
+                    mem: a.load.mem,
+                    key: p(a.load.key)?,
+                }),
+                rizin_sys::RZ_IL_OP_LOADW => Some(IlPure::LoadW {
+                    mem: a.loadw.mem,
+                    key: p(a.loadw.key)?,
+                    bits: a.loadw.n_bits,
+                }),
+                rizin_sys::RZ_IL_OP_FLOAT => Some(IlPure::Float {
+                    format: a.float_.r,
+                    bv: p(a.float_.bv)?,
+                }),
+                rizin_sys::RZ_IL_OP_FBITS => un(UnOp::Fbits, a.fbits.f),
+                rizin_sys::RZ_IL_OP_IS_FINITE => un(UnOp::IsFinite, a.is_finite.f),
+                rizin_sys::RZ_IL_OP_IS_NAN => un(UnOp::IsNan, a.is_nan.f),
+                rizin_sys::RZ_IL_OP_IS_INF => un(UnOp::IsInf, a.is_inf.f),
+                rizin_sys::RZ_IL_OP_IS_FZERO => un(UnOp::IsFzero, a.is_fzero.f),
+                rizin_sys::RZ_IL_OP_IS_FNEG => un(UnOp::IsFneg, a.is_fneg.f),
+                rizin_sys::RZ_IL_OP_IS_FPOS => un(UnOp::IsFpos, a.is_fpos.f),
+                rizin_sys::RZ_IL_OP_FNEG => un(UnOp::Fneg, a.fneg.f),
+                rizin_sys::RZ_IL_OP_FABS => un(UnOp::Fabs, a.fabs.f),
+                rizin_sys::RZ_IL_OP_FSUCC => un(UnOp::Fsucc, a.fsucc.f),
+                rizin_sys::RZ_IL_OP_FPRED => un(UnOp::Fpred, a.fpred.f),
+                rizin_sys::RZ_IL_OP_FCAST_INT => fcast_int!(false, a.fcast_int),
+                rizin_sys::RZ_IL_OP_FCAST_SINT => fcast_int!(true, a.fcast_sint),
+                rizin_sys::RZ_IL_OP_FCAST_FLOAT => fcast_float!(false, a.fcast_float),
+                rizin_sys::RZ_IL_OP_FCAST_SFLOAT => fcast_float!(true, a.fcast_sfloat),
+                rizin_sys::RZ_IL_OP_FCONVERT => Some(IlPure::Fconvert {
+                    format: a.fconvert.format,
+                    mode: a.fconvert.mode.into(),
+                    f: p(a.fconvert.f)?,
+                }),
+                rizin_sys::RZ_IL_OP_FREQUAL => Some(IlPure::Frequal {
+                    x: a.frequal.x.into(),
+                    y: a.frequal.y.into(),
+                }),
+                rizin_sys::RZ_IL_OP_FORDER => bin(BinOp::Forder, a.forder.x, a.forder.y),
+                rizin_sys::RZ_IL_OP_FROUND => fun!(FloatUnOp::Round, a.fround),
+                rizin_sys::RZ_IL_OP_FSQRT => fun!(FloatUnOp::Sqrt, a.fsqrt),
+                rizin_sys::RZ_IL_OP_FRSQRT => fun!(FloatUnOp::Rsqrt, a.frsqrt),
+                rizin_sys::RZ_IL_OP_FADD => fbin!(FloatBinOp::Add, a.fadd),
+                rizin_sys::RZ_IL_OP_FSUB => fbin!(FloatBinOp::Sub, a.fsub),
+                rizin_sys::RZ_IL_OP_FMUL => fbin!(FloatBinOp::Mul, a.fmul),
+                rizin_sys::RZ_IL_OP_FDIV => fbin!(FloatBinOp::Div, a.fdiv),
+                rizin_sys::RZ_IL_OP_FMOD => fbin!(FloatBinOp::Mod, a.fmod),
+                rizin_sys::RZ_IL_OP_FHYPOT => fbin!(FloatBinOp::Hypot, a.fhypot),
+                rizin_sys::RZ_IL_OP_FPOW => fbin!(FloatBinOp::Pow, a.fpow),
+                rizin_sys::RZ_IL_OP_FMAD => Some(IlPure::Fmad {
+                    mode: a.fmad.rmode.into(),
+                    x: p(a.fmad.x)?,
+                    y: p(a.fmad.y)?,
+                    z: p(a.fmad.z)?,
+                }),
+                rizin_sys::RZ_IL_OP_FROOTN => fint!(FloatIntOp::Rootn, a.frootn),
+                rizin_sys::RZ_IL_OP_FPOWN => fint!(FloatIntOp::Pown, a.fpown),
+                rizin_sys::RZ_IL_OP_FCOMPOUND => fint!(FloatIntOp::Compound, a.fcompound),
+                _ => None,
+            }
+        }
+    }
+
+    /// Builds a rizin op tree owned by the caller, e.g. for `RzAnalysisOp::il_op`.
+    /// Fails if a name contains a nul byte.
+    pub fn to_raw(&self) -> Result<*mut rizin_sys::RzILOpPure> {
+        self.check_names()?;
+        Ok(self.build())
+    }
+
+    fn check_names(&self) -> Result<()> {
+        if let IlPure::Var { name, .. } | IlPure::Let { name, .. } = self {
+            check_name(name)?;
+        }
+        self.children()
+            .into_iter()
+            .try_for_each(IlPure::check_names)
+    }
+
+    fn build(&self) -> *mut rizin_sys::RzILOpPure {
+        unsafe {
+            match self {
+                IlPure::Var { name, kind } => {
+                    rizin_sys::rz_il_op_new_var(intern(name), (*kind).into())
+                }
+                IlPure::Ite {
+                    cond,
+                    then,
+                    otherwise,
+                } => rizin_sys::rz_il_op_new_ite(cond.build(), then.build(), otherwise.build()),
+                IlPure::Let { name, exp, body } => {
+                    rizin_sys::rz_il_op_new_let(intern(name), exp.build(), body.build())
+                }
+                IlPure::Bool(false) => rizin_sys::rz_il_op_new_b0(),
+                IlPure::Bool(true) => rizin_sys::rz_il_op_new_b1(),
+                IlPure::Bitv(bv) => rizin_sys::rz_il_op_new_bitv(bv.clone().into_raw()),
+                IlPure::Unary(op, x) => {
+                    let x = x.build();
+                    match op {
+                        UnOp::Inv => rizin_sys::rz_il_op_new_bool_inv(x),
+                        UnOp::Msb => rizin_sys::rz_il_op_new_msb(x),
+                        UnOp::Lsb => rizin_sys::rz_il_op_new_lsb(x),
+                        UnOp::IsZero => rizin_sys::rz_il_op_new_is_zero(x),
+                        UnOp::Neg => rizin_sys::rz_il_op_new_neg(x),
+                        UnOp::LogNot => rizin_sys::rz_il_op_new_log_not(x),
+                        UnOp::Fbits => rizin_sys::rz_il_op_new_fbits(x),
+                        UnOp::IsFinite => rizin_sys::rz_il_op_new_is_finite(x),
+                        UnOp::IsNan => rizin_sys::rz_il_op_new_is_nan(x),
+                        UnOp::IsInf => rizin_sys::rz_il_op_new_is_inf(x),
+                        UnOp::IsFzero => rizin_sys::rz_il_op_new_is_fzero(x),
+                        UnOp::IsFneg => rizin_sys::rz_il_op_new_is_fneg(x),
+                        UnOp::IsFpos => rizin_sys::rz_il_op_new_is_fpos(x),
+                        UnOp::Fneg => rizin_sys::rz_il_op_new_fneg(x),
+                        UnOp::Fabs => rizin_sys::rz_il_op_new_fabs(x),
+                        UnOp::Fsucc => rizin_sys::rz_il_op_new_fsucc(x),
+                        UnOp::Fpred => rizin_sys::rz_il_op_new_fpred(x),
+                    }
+                }
+                IlPure::Binary(op, x, y) => {
+                    let (x, y) = (x.build(), y.build());
+                    match op {
+                        BinOp::And => rizin_sys::rz_il_op_new_bool_and(x, y),
+                        BinOp::Or => rizin_sys::rz_il_op_new_bool_or(x, y),
+                        BinOp::Xor => rizin_sys::rz_il_op_new_bool_xor(x, y),
+                        BinOp::Add => rizin_sys::rz_il_op_new_add(x, y),
+                        BinOp::Sub => rizin_sys::rz_il_op_new_sub(x, y),
+                        BinOp::Mul => rizin_sys::rz_il_op_new_mul(x, y),
+                        BinOp::Div => rizin_sys::rz_il_op_new_div(x, y),
+                        BinOp::Sdiv => rizin_sys::rz_il_op_new_sdiv(x, y),
+                        BinOp::Mod => rizin_sys::rz_il_op_new_mod(x, y),
+                        BinOp::Smod => rizin_sys::rz_il_op_new_smod(x, y),
+                        BinOp::LogAnd => rizin_sys::rz_il_op_new_log_and(x, y),
+                        BinOp::LogOr => rizin_sys::rz_il_op_new_log_or(x, y),
+                        BinOp::LogXor => rizin_sys::rz_il_op_new_log_xor(x, y),
+                        BinOp::Eq => rizin_sys::rz_il_op_new_eq(x, y),
+                        BinOp::Sle => rizin_sys::rz_il_op_new_sle(x, y),
+                        BinOp::Ule => rizin_sys::rz_il_op_new_ule(x, y),
+                        BinOp::Append => rizin_sys::rz_il_op_new_append(x, y),
+                        BinOp::Forder => rizin_sys::rz_il_op_new_forder(x, y),
+                    }
+                }
+                IlPure::ShiftRight { fill, x, y } => {
+                    rizin_sys::rz_il_op_new_shiftr(fill.build(), x.build(), y.build())
+                }
+                IlPure::ShiftLeft { fill, x, y } => {
+                    rizin_sys::rz_il_op_new_shiftl(fill.build(), x.build(), y.build())
+                }
+                IlPure::Cast { length, fill, val } => {
+                    rizin_sys::rz_il_op_new_cast(*length, fill.build(), val.build())
+                }
+                IlPure::Load { mem, key } => rizin_sys::rz_il_op_new_load(*mem, key.build()),
+                IlPure::LoadW { mem, key, bits } => {
+                    rizin_sys::rz_il_op_new_loadw(*mem, key.build(), *bits)
+                }
+                IlPure::Float { format, bv } => rizin_sys::rz_il_op_new_float(*format, bv.build()),
+                IlPure::FcastInt {
+                    signed: false,
+                    length,
+                    mode,
+                    f,
+                } => rizin_sys::rz_il_op_new_fcast_int(*length, (*mode).into(), f.build()),
+                IlPure::FcastInt {
+                    signed: true,
+                    length,
+                    mode,
+                    f,
+                } => rizin_sys::rz_il_op_new_fcast_sint(*length, (*mode).into(), f.build()),
+                IlPure::FcastFloat {
+                    signed: false,
+                    format,
+                    mode,
+                    bv,
+                } => rizin_sys::rz_il_op_new_fcast_float(*format, (*mode).into(), bv.build()),
+                IlPure::FcastFloat {
+                    signed: true,
+                    format,
+                    mode,
+                    bv,
+                } => rizin_sys::rz_il_op_new_fcast_sfloat(*format, (*mode).into(), bv.build()),
+                IlPure::Fconvert { format, mode, f } => {
+                    rizin_sys::rz_il_op_new_fconvert(*format, (*mode).into(), f.build())
+                }
+                IlPure::Frequal { x, y } => {
+                    rizin_sys::rz_il_op_new_frequal((*x).into(), (*y).into())
+                }
+                IlPure::FloatUnary { op, mode, f } => {
+                    let (mode, f) = ((*mode).into(), f.build());
+                    match op {
+                        FloatUnOp::Round => rizin_sys::rz_il_op_new_fround(mode, f),
+                        FloatUnOp::Sqrt => rizin_sys::rz_il_op_new_fsqrt(mode, f),
+                        FloatUnOp::Rsqrt => rizin_sys::rz_il_op_new_frsqrt(mode, f),
+                    }
+                }
+                IlPure::FloatBinary { op, mode, x, y } => {
+                    let (mode, x, y) = ((*mode).into(), x.build(), y.build());
+                    match op {
+                        FloatBinOp::Add => rizin_sys::rz_il_op_new_fadd(mode, x, y),
+                        FloatBinOp::Sub => rizin_sys::rz_il_op_new_fsub(mode, x, y),
+                        FloatBinOp::Mul => rizin_sys::rz_il_op_new_fmul(mode, x, y),
+                        FloatBinOp::Div => rizin_sys::rz_il_op_new_fdiv(mode, x, y),
+                        FloatBinOp::Mod => rizin_sys::rz_il_op_new_fmod(mode, x, y),
+                        FloatBinOp::Hypot => rizin_sys::rz_il_op_new_fhypot(mode, x, y),
+                        FloatBinOp::Pow => rizin_sys::rz_il_op_new_fpow(mode, x, y),
+                    }
+                }
+                IlPure::Fmad { mode, x, y, z } => {
+                    rizin_sys::rz_il_op_new_fmad((*mode).into(), x.build(), y.build(), z.build())
+                }
+                IlPure::FloatInt { op, mode, f, n } => {
+                    let (mode, f, n) = ((*mode).into(), f.build(), n.build());
+                    match op {
+                        FloatIntOp::Rootn => rizin_sys::rz_il_op_new_frootn(mode, f, n),
+                        FloatIntOp::Pown => rizin_sys::rz_il_op_new_fpown(mode, f, n),
+                        FloatIntOp::Compound => rizin_sys::rz_il_op_new_fcompound(mode, f, n),
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn children(&self) -> Vec<&IlPure> {
+        match self {
+            IlPure::Var { .. } | IlPure::Bool(_) | IlPure::Bitv(_) | IlPure::Frequal { .. } => {
+                vec![]
+            }
+            IlPure::Unary(_, x)
+            | IlPure::Load { key: x, .. }
+            | IlPure::LoadW { key: x, .. }
+            | IlPure::Float { bv: x, .. }
+            | IlPure::FcastInt { f: x, .. }
+            | IlPure::FcastFloat { bv: x, .. }
+            | IlPure::Fconvert { f: x, .. }
+            | IlPure::FloatUnary { f: x, .. } => vec![&**x],
+            IlPure::Binary(_, x, y)
+            | IlPure::Let {
+                exp: x, body: y, ..
+            }
+            | IlPure::Cast {
+                fill: x, val: y, ..
+            }
+            | IlPure::FloatBinary { x, y, .. }
+            | IlPure::FloatInt { f: x, n: y, .. } => vec![&**x, &**y],
+            IlPure::Ite {
+                cond: x,
+                then: y,
+                otherwise: z,
+            }
+            | IlPure::ShiftRight {
+                fill: x,
+                x: y,
+                y: z,
+            }
+            | IlPure::ShiftLeft {
+                fill: x,
+                x: y,
+                y: z,
+            }
+            | IlPure::Fmad { x, y, z, .. } => vec![&**x, &**y, &**z],
+        }
+    }
+
+    pub fn children_mut(&mut self) -> Vec<&mut IlPure> {
+        match self {
+            IlPure::Var { .. } | IlPure::Bool(_) | IlPure::Bitv(_) | IlPure::Frequal { .. } => {
+                vec![]
+            }
+            IlPure::Unary(_, x)
+            | IlPure::Load { key: x, .. }
+            | IlPure::LoadW { key: x, .. }
+            | IlPure::Float { bv: x, .. }
+            | IlPure::FcastInt { f: x, .. }
+            | IlPure::FcastFloat { bv: x, .. }
+            | IlPure::Fconvert { f: x, .. }
+            | IlPure::FloatUnary { f: x, .. } => vec![&mut **x],
+            IlPure::Binary(_, x, y)
+            | IlPure::Let {
+                exp: x, body: y, ..
+            }
+            | IlPure::Cast {
+                fill: x, val: y, ..
+            }
+            | IlPure::FloatBinary { x, y, .. }
+            | IlPure::FloatInt { f: x, n: y, .. } => vec![&mut **x, &mut **y],
+            IlPure::Ite {
+                cond: x,
+                then: y,
+                otherwise: z,
+            }
+            | IlPure::ShiftRight {
+                fill: x,
+                x: y,
+                y: z,
+            }
+            | IlPure::ShiftLeft {
+                fill: x,
+                x: y,
+                y: z,
+            }
+            | IlPure::Fmad { x, y, z, .. } => vec![&mut **x, &mut **y, &mut **z],
+        }
+    }
+}
+
+impl IlEffect {
+    /// Copies the op tree rooted at `op`. Returns `None` for null pointers and unknown opcodes.
+    pub unsafe fn from_raw(op: *const rizin_sys::RzILOpEffect) -> Option<Self> {
+        let op = unsafe { op.as_ref()? };
+        let p = |x: *mut rizin_sys::RzILOpPure| unsafe { IlPure::from_raw(x) };
+        let e = |x: *mut rizin_sys::RzILOpEffect| unsafe { Self::from_raw(x) }.map(Box::new);
+        let a = &op.op;
+        unsafe {
+            match op.code {
+                rizin_sys::RZ_IL_OP_EMPTY => Some(IlEffect::Empty),
+                rizin_sys::RZ_IL_OP_NOP => Some(IlEffect::Nop),
+                rizin_sys::RZ_IL_OP_STORE => Some(IlEffect::Store {
+                    mem: a.store.mem,
+                    key: p(a.store.key)?,
+                    value: p(a.store.value)?,
+                }),
+                rizin_sys::RZ_IL_OP_STOREW => Some(IlEffect::StoreW {
+                    mem: a.storew.mem,
+                    key: p(a.storew.key)?,
+                    value: p(a.storew.value)?,
+                }),
+                rizin_sys::RZ_IL_OP_SET => Some(IlEffect::Set {
+                    name: cstr_to_string(a.set.v)?,
+                    is_local: a.set.is_local,
+                    x: p(a.set.x)?,
+                }),
+                rizin_sys::RZ_IL_OP_JMP => Some(IlEffect::Jmp(p(a.jmp.dst)?)),
+                rizin_sys::RZ_IL_OP_GOTO => Some(IlEffect::Goto(cstr_to_string(a.goto_.lbl)?)),
+                rizin_sys::RZ_IL_OP_SEQ => Some(IlEffect::Seq(e(a.seq.x)?, e(a.seq.y)?)),
+                rizin_sys::RZ_IL_OP_BLK => Some(IlEffect::Blk {
+                    label: cstr_to_string(a.blk.label),
+                    data: e(a.blk.data_eff)?,
+                    ctrl: e(a.blk.ctrl_eff)?,
+                }),
+                rizin_sys::RZ_IL_OP_REPEAT => Some(IlEffect::Repeat {
+                    cond: p(a.repeat.condition)?,
+                    body: e(a.repeat.data_eff)?,
+                }),
+                rizin_sys::RZ_IL_OP_BRANCH => Some(IlEffect::Branch {
+                    cond: p(a.branch.condition)?,
+                    then: e(a.branch.true_eff)?,
+                    otherwise: e(a.branch.false_eff)?,
+                }),
+                _ => None,
+            }
+        }
+    }
+
+    /// Builds a rizin op tree owned by the caller, e.g. for `RzAnalysisOp::il_op`.
+    /// Fails if a name or label contains a nul byte.
+    pub fn to_raw(&self) -> Result<*mut rizin_sys::RzILOpEffect> {
+        self.check_names()?;
+        Ok(self.build())
+    }
+
+    fn check_names(&self) -> Result<()> {
+        if let IlEffect::Set { name, .. }
+        | IlEffect::Goto(name)
+        | IlEffect::Blk {
+            label: Some(name), ..
+        } = self
+        {
+            check_name(name)?;
+        }
+        self.pures().into_iter().try_for_each(IlPure::check_names)?;
+        self.effects()
+            .into_iter()
+            .try_for_each(IlEffect::check_names)
+    }
+
+    fn build(&self) -> *mut rizin_sys::RzILOpEffect {
+        unsafe {
+            match self {
+                IlEffect::Empty => rizin_sys::rz_il_op_new_empty(),
+                IlEffect::Nop => rizin_sys::rz_il_op_new_nop(),
+                IlEffect::Store { mem, key, value } => {
+                    rizin_sys::rz_il_op_new_store(*mem, key.build(), value.build())
+                }
+                IlEffect::StoreW { mem, key, value } => {
+                    rizin_sys::rz_il_op_new_storew(*mem, key.build(), value.build())
+                }
+                IlEffect::Set { name, is_local, x } => {
+                    rizin_sys::rz_il_op_new_set(intern(name), *is_local, x.build())
+                }
+                IlEffect::Jmp(dst) => rizin_sys::rz_il_op_new_jmp(dst.build()),
+                IlEffect::Goto(label) => rizin_sys::rz_il_op_new_goto(intern(label)),
+                IlEffect::Seq(x, y) => rizin_sys::rz_il_op_new_seq(x.build(), y.build()),
+                IlEffect::Blk { label, data, ctrl } => rizin_sys::rz_il_op_new_blk(
+                    label.as_deref().map_or(null(), |l| intern(l)),
+                    data.build(),
+                    ctrl.build(),
+                ),
+                IlEffect::Repeat { cond, body } => {
+                    rizin_sys::rz_il_op_new_repeat(cond.build(), body.build())
+                }
+                IlEffect::Branch {
+                    cond,
+                    then,
+                    otherwise,
+                } => rizin_sys::rz_il_op_new_branch(cond.build(), then.build(), otherwise.build()),
+            }
+        }
+    }
+
+    pub fn pures(&self) -> Vec<&IlPure> {
+        match self {
+            IlEffect::Store { key, value, .. } | IlEffect::StoreW { key, value, .. } => {
+                vec![key, value]
+            }
+            IlEffect::Set { x, .. }
+            | IlEffect::Jmp(x)
+            | IlEffect::Repeat { cond: x, .. }
+            | IlEffect::Branch { cond: x, .. } => vec![x],
+            _ => vec![],
+        }
+    }
+
+    pub fn pures_mut(&mut self) -> Vec<&mut IlPure> {
+        match self {
+            IlEffect::Store { key, value, .. } | IlEffect::StoreW { key, value, .. } => {
+                vec![key, value]
+            }
+            IlEffect::Set { x, .. }
+            | IlEffect::Jmp(x)
+            | IlEffect::Repeat { cond: x, .. }
+            | IlEffect::Branch { cond: x, .. } => vec![x],
+            _ => vec![],
+        }
+    }
+
+    pub fn effects(&self) -> Vec<&IlEffect> {
+        match self {
+            IlEffect::Seq(x, y)
+            | IlEffect::Blk {
+                data: x, ctrl: y, ..
+            }
+            | IlEffect::Branch {
+                then: x,
+                otherwise: y,
+                ..
+            } => vec![&**x, &**y],
+            IlEffect::Repeat { body, .. } => vec![&**body],
+            _ => vec![],
+        }
+    }
+
+    pub fn effects_mut(&mut self) -> Vec<&mut IlEffect> {
+        match self {
+            IlEffect::Seq(x, y)
+            | IlEffect::Blk {
+                data: x, ctrl: y, ..
+            }
+            | IlEffect::Branch {
+                then: x,
+                otherwise: y,
+                ..
+            } => vec![&mut **x, &mut **y],
+            IlEffect::Repeat { body, .. } => vec![&mut **body],
+            _ => vec![],
+        }
+    }
+}
+
+/// Read-only traversal. Overridden methods call [`walk_pure`]/[`walk_effect`] to descend.
+pub trait IlVisitor {
+    fn visit_pure(&mut self, op: &IlPure) {
+        walk_pure(self, op)
+    }
+
+    fn visit_effect(&mut self, op: &IlEffect) {
+        walk_effect(self, op)
+    }
+}
+
+pub fn walk_pure<V: IlVisitor + ?Sized>(v: &mut V, op: &IlPure) {
+    op.children().into_iter().for_each(|c| v.visit_pure(c));
+}
+
+pub fn walk_effect<V: IlVisitor + ?Sized>(v: &mut V, op: &IlEffect) {
+    op.pures().into_iter().for_each(|p| v.visit_pure(p));
+    op.effects().into_iter().for_each(|e| v.visit_effect(e));
+}
+
+/// Rewriting traversal. Overridden methods call [`fold_pure_children`]/[`fold_effect_children`] to descend.
+pub trait IlFold {
+    fn fold_pure(&mut self, op: IlPure) -> IlPure {
+        fold_pure_children(self, op)
+    }
+
+    fn fold_effect(&mut self, op: IlEffect) -> IlEffect {
+        fold_effect_children(self, op)
+    }
+}
+
+pub fn fold_pure_children<F: IlFold + ?Sized>(f: &mut F, mut op: IlPure) -> IlPure {
+    op.children_mut().into_iter().for_each(|c| {
+        let child = std::mem::replace(c, IlPure::Bool(false));
+        *c = f.fold_pure(child);
+    });
+    op
+}
+
+pub fn fold_effect_children<F: IlFold + ?Sized>(f: &mut F, mut op: IlEffect) -> IlEffect {
+    op.pures_mut().into_iter().for_each(|c| {
+        let child = std::mem::replace(c, IlPure::Bool(false));
+        *c = f.fold_pure(child);
+    });
+    op.effects_mut().into_iter().for_each(|c| {
+        let child = std::mem::replace(c, IlEffect::Empty);
+        *c = f.fold_effect(child);
+    });
+    op
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::il::{
+        IlEffect, IlFold, IlPure, IlVisitor, fold_pure_children, walk_effect, walk_pure,
+    };
+    use crate::{Error, RzCore};
+
+    #[derive(Default)]
+    struct Sets(Vec<String>);
+
+    impl IlVisitor for Sets {
+        fn visit_effect(&mut self, op: &IlEffect) {
+            if let IlEffect::Set { name, .. } = op {
+                self.0.push(name.clone());
+            }
+            walk_effect(self, op)
+        }
+    }
+
+    #[derive(Default)]
+    struct Vars(Vec<String>);
+
+    impl IlVisitor for Vars {
+        fn visit_pure(&mut self, op: &IlPure) {
+            if let IlPure::Var { name, .. } = op {
+                self.0.push(name.clone());
+            }
+            walk_pure(self, op)
+        }
+    }
+
+    struct RenameVar;
+
+    impl IlFold for RenameVar {
+        fn fold_pure(&mut self, op: IlPure) -> IlPure {
+            match fold_pure_children(self, op) {
+                IlPure::Var { name, kind } => IlPure::Var {
+                    name: name.to_uppercase(),
+                    kind,
+                },
+                op => op,
+            }
+        }
+    }
+
+    #[test]
+    fn test_il_tree() {
+        let core = RzCore::new();
+        core.config_set("analysis.arch", "x86").unwrap();
+        core.config_set("asm.bits", "32").unwrap();
+        // add eax, ebx
+        let op = core
+            .analysis_op(&[0x01, 0xd8], 0, rizin_sys::RZ_ANALYSIS_OP_MASK_IL)
+            .unwrap();
+        let il = op.il().unwrap();
+
+        let raw = il.to_raw().unwrap();
+        assert_eq!(unsafe { IlEffect::from_raw(raw) }.as_ref(), Some(&il));
+        unsafe { rizin_sys::rz_il_op_effect_free(raw) };
+
+        let mut sets = Sets::default();
+        sets.visit_effect(&il);
+        assert!(sets.0.iter().any(|s| s == "eax"));
+
+        let mut vars = Vars::default();
+        vars.visit_effect(&il);
+        assert!(vars.0.iter().any(|v| v == "ebx"));
+
+        let mut vars = Vars::default();
+        vars.visit_effect(&RenameVar.fold_effect(il));
+        assert!(!vars.0.is_empty());
+        assert!(vars.0.iter().all(|v| v.chars().all(|c| !c.is_lowercase())));
+        assert!(vars.0.iter().any(|v| v == "EBX"));
+
+        let bad = IlEffect::Goto("a\0b".into());
+        assert!(matches!(bad.to_raw(), Err(Error::NulInString(_))));
+    }
+}
@@ -0,0 +1,347 @@
+use crate::RzCore;
+use crate::bitvector::BitVector;
+use crate::error::{Error, Result, with_log};
+use crate::float::Float;
+use crate::util::{RzPVector, cstr_to_string};
+use std::ffi::CString;
+use std::mem::ManuallyDrop;
+use std::ptr::{NonNull, null_mut};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IlValue {
+    Bool(bool),
+    Bitv(BitVector),
+    Float(Float),
+}
+
+impl IlValue {
+    pub(crate) unsafe fn from_raw(val: *const rizin_sys::RzILVal) -> Option<Self> {
+        let val = unsafe { val.as_ref()? };
+        unsafe {
+            match val.type_ {
+                rizin_sys::RZ_IL_TYPE_PURE_BOOL => val.data.b.as_ref().map(|b| IlValue::Bool(b.b)),
+                rizin_sys::RZ_IL_TYPE_PURE_BITVECTOR => {
+                    BitVector::from_raw_dup(val.data.bv).map(IlValue::Bitv)
+                }
+                rizin_sys::RZ_IL_TYPE_PURE_FLOAT => {
+                    Float::from_raw_dup(val.data.f).map(IlValue::Float)
+                }
+                _ => None,
+            }
+        }
+    }
+
+    pub fn as_bitv(&self) -> Option<&BitVector> {
+        match self {
+            IlValue::Bitv(bv) => Some(bv),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<&Float> {
+        match self {
+            IlValue::Float(f) => Some(f),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IlEvent {
+    Exception(String),
+    PcWrite {
+        old: BitVector,
+        new: BitVector,
+    },
+    MemRead {
+        addr: BitVector,
+        value: Option<BitVector>,
+    },
+    VarRead {
+        name: String,
+        value: Option<IlValue>,
+    },
+    MemWrite {
+        addr: BitVector,
+        old: Option<BitVector>,
+        new: BitVector,
+    },
+    VarWrite {
+        name: String,
+        old: Option<IlValue>,
+        new: IlValue,
+    },
+}
+
+impl IlEvent {
+    unsafe fn from_raw(ev: &rizin_sys::RzILEvent) -> Option<Self> {
+        let d = &ev.data;
+        unsafe {
+            match ev.type_ {
+                rizin_sys::RZ_IL_EVENT_EXCEPTION => {
+                    Some(IlEvent::Exception(cstr_to_string(d.exception)?))
+                }
+                rizin_sys::RZ_IL_EVENT_PC_WRITE => Some(IlEvent::PcWrite {
+                    old: BitVector::from_raw_dup(d.pc_write.old_pc)?,
+                    new: BitVector::from_raw_dup(d.pc_write.new_pc)?,
+                }),
+                rizin_sys::RZ_IL_EVENT_MEM_READ => Some(IlEvent::MemRead {
+                    addr: BitVector::from_raw_dup(d.mem_read.address)?,
+                    value: BitVector::from_raw_dup(d.mem_read.value),
+                }),
+                rizin_sys::RZ_IL_EVENT_VAR_READ => Some(IlEvent::VarRead {
+                    name: cstr_to_string(d.var_read.variable)?,
+                    value: IlValue::from_raw(d.var_read.value),
+                }),
+                rizin_sys::RZ_IL_EVENT_MEM_WRITE => Some(IlEvent::MemWrite {
+                    addr: BitVector::from_raw_dup(d.mem_write.address)?,
+                    old: BitVector::from_raw_dup(d.mem_write.old_value),
+                    new: BitVector::from_raw_dup(d.mem_write.new_value)?,
+                }),
+                rizin_sys::RZ_IL_EVENT_VAR_WRITE => Some(IlEvent::VarWrite {
+                    name: cstr_to_string(d.var_write.variable)?,
+                    old: IlValue::from_raw(d.var_write.old_value),
+                    new: IlValue::from_raw(d.var_write.new_value)?,
+                }),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// IL VM bound to the core's current arch, register profile and IO.
+pub struct IlVm<'a> {
+    core: &'a RzCore,
+    pub inner: NonNull<rizin_sys::RzAnalysisILVM>,
+}
+
+impl Drop for IlVm<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            rizin_sys::rz_analysis_il_vm_free(self.inner.as_ptr());
+        }
+    }
+}
+
+impl<'a> IlVm<'a> {
+    pub fn new(core: &'a RzCore) -> Result<Self> {
+        let vm = unsafe {
+            let analysis = core.analysis();
+            rizin_sys::rz_analysis_il_vm_new(analysis, (*analysis).reg)
+        };
+        NonNull::new(vm)
+            .map(|inner| Self { core, inner })
+            .ok_or(Error::IlUnsupported)
+    }
+
+    fn vm(&self) -> *mut rizin_sys::RzILVM {
+        unsafe { self.inner.as_ref().vm }
+    }
+
+    pub fn pc(&self) -> u64 {
+        unsafe { rizin_sys::rz_bv_to_ut64((*self.vm()).pc) }
+    }
+
+    pub fn set_pc(&mut self, addr: u64) {
+        unsafe { rizin_sys::rz_bv_set_from_ut64((*self.vm()).pc, addr) };
+    }
+
+    pub fn reg(&self, name: &str) -> Result<IlValue> {
+        let cname = CString::new(name)?;
+        unsafe {
+            IlValue::from_raw(rizin_sys::rz_il_vm_get_var_value(
+                self.vm(),
+                rizin_sys::RZ_IL_VAR_KIND_GLOBAL,
+                cname.as_ptr(),
+            ))
+        }
+        .ok_or_else(|| Error::UnknownRegister(name.to_owned()))
+    }
+
+    pub fn set_reg(&mut self, name: &str, value: &BitVector) -> Result<()> {
+        let cname = CString::new(name)?;
+        // Setting an unknown global is silently ignored by rizin.
+        let var = unsafe {
+            rizin_sys::rz_il_vm_get_var(self.vm(), rizin_sys::RZ_IL_VAR_KIND_GLOBAL, cname.as_ptr())
+        };
+        let Some(var) = (unsafe { var.as_ref() }) else {
+            return Err(Error::UnknownRegister(name.to_owned()));
+        };
+        let width = match var.sort.type_ {
+            rizin_sys::RZ_IL_TYPE_PURE_BITVECTOR => Some(unsafe { var.sort.props.bv.length }),
+            _ => None,
+        };
+        if width != Some(value.len()) {
+            return Err(Error::IlSortMismatch {
+                name: name.to_owned(),
+                bits: value.len(),
+            });
+        }
+        let val = unsafe { rizin_sys::rz_il_value_new_bitv(value.clone().into_raw()) };
+        if val.is_null() {
+            return Err(Error::rizin(format!("set register {}", name), None));
+        }
+        unsafe { rizin_sys::rz_il_vm_set_global_var(self.vm(), cname.as_ptr(), val) };
+        Ok(())
+    }
+
+    /// Loads `bits` bits from memory 0 at `addr`, honoring the arch's endianness.
+    pub fn read_mem(&mut self, addr: u64, bits: u32) -> Result<BitVector> {
+        let key = BitVector::from_u64(self.addr_bits(), addr);
+        BitVector::from_raw(unsafe {
+            rizin_sys::rz_il_vm_mem_loadw(self.vm(), 0, key.as_ptr(), bits)
+        })
+        .ok_or(Error::ReadFailed { addr })
+    }
+
+    /// Stores `value` to memory 0 at `addr`, honoring the arch's endianness.
+    pub fn write_mem(&mut self, addr: u64, value: &BitVector) -> Result<()> {
+        let key = BitVector::from_u64(self.addr_bits(), addr);
+        let vm = self.vm();
+        // rz_il_vm_mem_storew drops the store status, so go to the memory directly.
+        let (stored, log) = with_log(|| unsafe {
+            let mem = rizin_sys::rz_il_vm_get_mem(vm, 0);
+            !mem.is_null()
+                && rizin_sys::rz_il_mem_storew(mem, key.as_ptr(), value.as_ptr(), (*vm).big_endian)
+        });
+        if stored {
+            Ok(())
+        } else {
+            Err(Error::rizin(format!("il write at {:#x}", addr), log))
+        }
+    }
+
+    fn addr_bits(&self) -> u32 {
+        unsafe { rizin_sys::rz_bv_len((*self.vm()).pc) }
+    }
+
+    fn take_events(&self) -> Vec<IlEvent> {
+        RzPVector::<rizin_sys::RzILEvent>::from_raw(unsafe { (*self.vm()).events })
+            .map(ManuallyDrop::new)
+            .map(|evs| {
+                evs.iter()
+                    .filter_map(|ev| unsafe { ev.as_ref() })
+                    .filter_map(|ev| unsafe { IlEvent::from_raw(ev) })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Executes the instruction at the current pc and returns the events it produced.
+    pub fn step(&mut self) -> Result<Vec<IlEvent>> {
+        let res = unsafe {
+            rizin_sys::rz_analysis_il_vm_step(self.core.analysis(), self.inner.as_ptr(), null_mut())
+        };
+        if res == rizin_sys::RZ_ANALYSIS_IL_STEP_RESULT_SUCCESS {
+            Ok(self.take_events())
+        } else {
+            Err(Error::IlStepFailed {
+                pc: self.pc(),
+                code: res,
+            })
+        }
+    }
+
+    /// Steps until the pc reaches `addr` or `max_steps` instructions ran, returning the step count.
+    pub fn run_until(
+        &mut self,
+        addr: u64,
+        max_steps: usize,
+        mut on_event: impl FnMut(IlEvent),
+    ) -> Result<usize> {
+        let mut steps = 0;
+        while self.pc() != addr && steps < max_steps {
+            self.step()?.into_iter().for_each(&mut on_event);
+            steps += 1;
+        }
+        Ok(steps)
+    }
+
+    /// Writes the VM's registers back to the core's register file.
+    pub fn sync_to_core(&self) {
+        unsafe {
+            rizin_sys::rz_analysis_il_vm_sync_to_reg(
+                self.inner.as_ptr(),
+                (*self.core.analysis()).reg,
+            )
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitvector::BitVector;
+    use crate::il_vm::{IlEvent, IlValue, IlVm};
+    use crate::reg::RegisterFile;
+    use crate::{Error, Perm, RzCore};
+
+    #[test]
+    fn test_il_vm_regs() {
+        let core = RzCore::new();
+        core.config_set("asm.arch", "x86").unwrap();
+        core.config_set("asm.bits", "32").unwrap();
+        let mut vm = IlVm::new(&core).unwrap();
+        vm.set_pc(0x1000);
+        assert_eq!(vm.pc(), 0x1000);
+        vm.set_reg("eax", &BitVector::from_u64(32, 0x1234)).unwrap();
+        assert_eq!(
+            vm.reg("eax").unwrap(),
+            IlValue::Bitv(BitVector::from_u64(32, 0x1234))
+        );
+        assert!(vm.reg("nonexistent").is_err());
+        assert!(matches!(
+            vm.set_reg("nonexistent", &BitVector::from_u64(32, 1)),
+            Err(Error::UnknownRegister(_))
+        ));
+    }
+
+    #[test]
+    fn test_il_vm_step() {
+        let core = RzCore::new();
+        core.config_set("asm.arch", "x86").unwrap();
+        core.config_set("asm.bits", "32").unwrap();
+        let _file = core.open("malloc://0x100", Perm::RWX, 0x1000).unwrap();
+        // mov eax, 1; add eax, 2
+        core.write_at(0x1000, &[0xb8, 0x01, 0x00, 0x00, 0x00, 0x83, 0xc0, 0x02])
+            .unwrap();
+        let mut vm = IlVm::new(&core).unwrap();
+        vm.set_pc(0x1000);
+
+        let events = vm.step().unwrap();
+        assert_eq!(vm.pc(), 0x1005);
+        assert!(events.contains(&IlEvent::VarWrite {
+            name: "eax".into(),
+            old: Some(IlValue::Bitv(BitVector::from_u64(32, 0))),
+            new: IlValue::Bitv(BitVector::from_u64(32, 1)),
+        }));
+
+        let mut events = Vec::new();
+        assert_eq!(vm.run_until(0x1008, 10, |ev| events.push(ev)).unwrap(), 1);
+        assert_eq!(vm.pc(), 0x1008);
+        assert_eq!(
+            vm.reg("eax").unwrap(),
+            IlValue::Bitv(BitVector::from_u64(32, 3))
+        );
+        assert!(
+            events
+                .iter()
+                .any(|ev| matches!(ev, IlEvent::VarWrite { name, .. } if name == "eax"))
+        );
+
+        vm.write_mem(0x1080, &BitVector::from_u64(32, 0xdeadbeef))
+            .unwrap();
+        assert_eq!(
+            vm.read_mem(0x1080, 32).unwrap(),
+            BitVector::from_u64(32, 0xdeadbeef)
+        );
+
+        assert!(matches!(
+            vm.set_reg("eax", &BitVector::from_u64(16, 1)),
+            Err(Error::IlSortMismatch { bits: 16, .. })
+        ));
+        vm.set_reg("eax", &BitVector::from_u64(32, 3)).unwrap();
+
+        vm.sync_to_core();
+        assert_eq!(RegisterFile::new(&core).unwrap().get("eax").unwrap(), 3);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::error::{Error, Result, with_log};
+use crate::util::{RzPVector, cstr_to_string};
+use crate::{Perm, RzCore};
+use std::ffi::CString;
+use std::mem::ManuallyDrop;
+
+/// Region of the virtual address space backed by a descriptor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoMap {
+    pub id: u32,
+    pub fd: i32,
+    pub from: u64,
+    /// Exclusive end address.
+    pub to: u64,
+    pub perm: Perm,
+    /// Offset of `from` inside the descriptor.
+    pub delta: u64,
+    pub name: Option<String>,
+}
+
+impl From<&rizin_sys::RzIOMap> for IoMap {
+    fn from(m: &rizin_sys::RzIOMap) -> Self {
+        Self {
+            id: m.id,
+            fd: m.fd,
+            from: m.itv.addr,
+            to: m.itv.addr.wrapping_add(m.itv.size),
+            perm: Perm(m.perm as u32),
+            delta: m.delta,
+            name: cstr_to_string(m.name),
+        }
+    }
+}
+
+/// File opened in the core's IO. The descriptor is closed on drop.
+pub struct IoFile<'a> {
+    core: &'a RzCore,
+    fd: i32,
+}
+
+impl Drop for IoFile<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            rizin_sys::rz_io_fd_close(self.core.io(), self.fd);
+        }
+    }
+}
+
+impl<'a> IoFile<'a> {
+    pub fn fd(&self) -> i32 {
+        self.fd
+    }
+
+    fn desc(&self) -> Option<&rizin_sys::RzIODesc> {
+        unsafe { rizin_sys::rz_io_desc_get(self.core.io(), self.fd).as_ref() }
+    }
+
+    pub fn uri(&self) -> Option<String> {
+        self.desc().and_then(|d| cstr_to_string(d.uri))
+    }
+
+    pub fn perm(&self) -> Perm {
+        self.desc().map_or(Perm::NONE, |d| Perm(d.perm as u32))
+    }
+
+    pub fn size(&self) -> u64 {
+        unsafe { rizin_sys::rz_io_fd_size(self.core.io(), self.fd) }
+    }
+
+    /// Reads at a file offset, bypassing maps.
+    pub fn read_at(&self, offset: u64, len: usize) -> Result<Vec<u8>> {
+        let mut buf = vec![0u8; len];
+        let n = unsafe {
+            rizin_sys::rz_io_fd_read_at(self.core.io(), self.fd, offset, buf.as_mut_ptr(), len as _)
+        };
+        if n < 0 {
+            return Err(Error::ReadFailed { addr: offset });
+        }
+        buf.truncate(n as usize);
+        Ok(buf)
+    }
+
+    /// Writes at a file offset, bypassing maps.
+    pub fn write_at(&self, offset: u64, bytes: &[u8]) -> Result<usize> {
+        let (n, log) = with_log(|| unsafe {
+            rizin_sys::rz_io_fd_write_at(
+                self.core.io(),
+                self.fd,
+                offset,
+                bytes.as_ptr(),
+                bytes.len() as _,
+            )
+        });
+        usize::try_from(n).map_err(|_| Error::rizin(format!("write at {:#x}", offset), log))
+    }
+
+    /// Maps belonging to this descriptor.
+    pub fn maps(&self) -> Vec<IoMap> {
+        self.core
+            .maps()
+            .into_iter()
+            .filter(|m| m.fd == self.fd)
+            .collect()
+    }
+}
+
+impl RzCore {
+    pub(crate) fn io(&self) -> *mut rizin_sys::RzIO {
+        unsafe { self.0.as_ref().io }
+    }
+
+    /// Opens `uri` (a path or any IO plugin uri such as `malloc://0x1000`) and maps
+    /// it at `base_addr`.
+    pub fn open(&self, uri: &str, perm: Perm, base_addr: u64) -> Result<IoFile<'_>> {
+        let curi = CString::new(uri)?;
+        let (desc, log) = with_log(|| unsafe {
+            rizin_sys::rz_io_open_at(self.io(), curi.as_ptr(), perm.0 as _, 0o644, base_addr)
+        });
+        unsafe { desc.as_ref() }
+            .map(|d| IoFile {
+                core: self,
+                fd: d.fd,
+            })
+            .ok_or(Error::OpenFailed {
+                path: uri.to_owned(),
+                log,
+            })
+    }
+
+    /// Reads through the maps; unmapped bytes read as `0xff`.
+    pub fn read_at(&self, addr: u64, len: usize) -> Result<Vec<u8>> {
+        let mut buf = vec![0u8; len];
+        if unsafe { rizin_sys::rz_io_read_at(self.io(), addr, buf.as_mut_ptr(), len as _) } {
+            Ok(buf)
+        } else {
+            Err(Error::ReadFailed { addr })
+        }
+    }
+
+    pub fn write_at(&self, addr: u64, bytes: &[u8]) -> Result<()> {
+        let (ok, log) = with_log(|| unsafe {
+            rizin_sys::rz_io_write_at(self.io(), addr, bytes.as_ptr(), bytes.len() as _)
+        });
+        if ok {
+            Ok(())
+        } else {
+            Err(Error::rizin(format!("write at {:#x}", addr), log))
+        }
+    }
+
+    /// Current seek, used as default address by commands and analysis.
+    pub fn offset(&self) -> u64 {
+        unsafe { self.0.as_ref().offset }
+    }
+
+    pub fn seek(&self, addr: u64) -> Result<()> {
+        if unsafe { rizin_sys::rz_core_seek(self.0.as_ptr(), addr, true) } {
+            Ok(())
+        } else {
+            Err(Error::rizin(format!("seek {:#x}", addr), None))
+        }
+    }
+
+    pub fn maps(&self) -> Vec<IoMap> {
+        RzPVector::<rizin_sys::RzIOMap>::from_raw(unsafe { rizin_sys::rz_io_maps(self.io()) })
+            .map(ManuallyDrop::new)
+            .map(|maps| {
+                maps.iter()
+                    .filter_map(|m| unsafe { m.as_ref() })
+                    .map(IoMap::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Perm, RzCore};
+
+    #[test]
+    fn test_io_rw() {
+        let core = RzCore::new();
+        let file = core.open("malloc://0x100", Perm::RW, 0x1000).unwrap();
+        assert_eq!(file.size(), 0x100);
+
+        core.write_at(0x1010, &[0xde, 0xad, 0xbe, 0xef]).unwrap();
+        assert_eq!(
+            core.read_at(0x1010, 4).unwrap(),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(file.read_at(0x10, 2).unwrap(), vec![0xde, 0xad]);
+
+        let maps = file.maps();
+        assert_eq!(maps.len(), 1);
+        assert_eq!((maps[0].from, maps[0].to), (0x1000, 0x1100));
+        assert!(maps[0].perm.contains(Perm::RW));
+
+        core.seek(0x1010).unwrap();
+        assert_eq!(core.offset(), 0x1010);
+
+        drop(file);
+        assert!(core.maps().is_empty());
+    }
+}
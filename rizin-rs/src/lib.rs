@@ -1,65 +1,72 @@
 #![allow(dead_code)]
 
+pub mod analysis;
+pub mod asm;
+pub mod bin;
+pub mod bitvector;
+pub mod buf;
+pub mod cmd;
+pub mod config;
+pub mod debug;
+pub mod disasm;
+pub mod error;
+pub mod flag;
+pub mod float;
+pub mod graph;
+pub mod il;
+pub mod il_vm;
+pub mod io;
+pub mod meta;
+pub mod op;
+pub mod plugin;
+pub mod project;
+pub mod reg;
+#[cfg(test)]
+mod test_util;
 pub mod util;
-
-use crate::util::RzStrBuf;
-use anyhow::anyhow;
-use core::str;
-use rizin_sys;
-use std::ffi::{CStr, CString};
-use std::path::PathBuf;
+pub mod xref;
+
+pub use crate::bin::RzBinFile;
+pub use crate::error::{Error, Result};
+pub use crate::op::RzAnalysisOp;
+#[doc(hidden)]
+pub use rizin_sys;
+use std::fmt;
+use std::ops::BitOr;
 use std::ptr::{NonNull, addr_of_mut};
-use std::str::FromStr;
 
-pub struct RzAnalysisOp(pub rizin_sys::RzAnalysisOp);
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Perm(pub u32);
 
-impl Drop for RzAnalysisOp {
-    fn drop(&mut self) {
-        unsafe {
-            rizin_sys::rz_analysis_op_fini(addr_of_mut!(self.0));
-        }
-    }
-}
+impl Perm {
+    pub const NONE: Perm = Perm(0);
+    pub const R: Perm = Perm(rizin_sys::RZ_PERM_R);
+    pub const W: Perm = Perm(rizin_sys::RZ_PERM_W);
+    pub const X: Perm = Perm(rizin_sys::RZ_PERM_X);
+    pub const RW: Perm = Perm(Perm::R.0 | Perm::W.0);
+    pub const RX: Perm = Perm(Perm::R.0 | Perm::X.0);
+    pub const RWX: Perm = Perm(Perm::R.0 | Perm::W.0 | Perm::X.0);
 
-impl RzAnalysisOp {
-    pub fn mnemonic(&self) -> anyhow::Result<&str> {
-        if self.0.mnemonic.is_null() {
-            Err(anyhow!("mnemonic is_null"))
-        } else {
-            let cstr = unsafe { CStr::from_ptr(self.0.mnemonic) };
-            cstr.to_str().map_err(|_| anyhow!("invalid cstr"))
-        }
+    pub fn contains(self, other: Perm) -> bool {
+        self.0 & other.0 == other.0
     }
+}
 
-    pub fn il_op(&self) -> Option<rizin_sys::RzAnalysisLiftedILOp> {
-        if self.0.il_op.is_null() {
-            None
-        } else {
-            Some(self.0.il_op)
-        }
-    }
+impl BitOr for Perm {
+    type Output = Perm;
 
-    pub fn il_str(&self, pretty: bool) -> Option<String> {
-        self.il_op().map(|op| {
-            let mut sb = RzStrBuf::new();
-            unsafe {
-                rizin_sys::rz_il_op_effect_stringify(op, addr_of_mut!(sb.0), pretty);
-            }
-            sb.to_string()
-        })
+    fn bitor(self, rhs: Perm) -> Perm {
+        Perm(self.0 | rhs.0)
     }
 }
 
-pub struct RzBinFile<'a> {
-    core: &'a RzCore,
-    pub inner: NonNull<rizin_sys::RzBinFile>,
-}
-
-impl Drop for RzBinFile<'_> {
-    fn drop(&mut self) {
-        unsafe {
-            rizin_sys::rz_bin_file_delete(self.core.0.as_ref().bin, self.inner.as_ptr());
-        }
+impl fmt::Display for Perm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        [(Perm::R, 'r'), (Perm::W, 'w'), (Perm::X, 'x')]
+            .iter()
+            .try_for_each(|(p, c)| {
+                fmt::Write::write_char(f, if self.contains(*p) { *c } else { '-' })
+            })
     }
 }
 
@@ -72,11 +79,13 @@ impl Drop for RzCore {
         unsafe {
             rizin_sys::rz_core_free(self.0.as_ptr());
         }
+        cmd::forget_core(self.0.as_ptr());
     }
 }
 
-impl<'a> RzCore {
+impl RzCore {
     pub fn new() -> Self {
+        error::install_log_hook();
         let core = unsafe { rizin_sys::rz_core_new() };
         Self(NonNull::new(core).unwrap())
     }
@@ -86,9 +95,9 @@ impl<'a> RzCore {
         bytes: &[u8],
         addr: usize,
         mask: rizin_sys::RzAnalysisOpMask,
-    ) -> anyhow::Result<RzAnalysisOp> {
+    ) -> Result<RzAnalysisOp> {
         let mut op: RzAnalysisOp = RzAnalysisOp(Default::default());
-        let res = unsafe {
+        let (res, log) = error::with_log(|| unsafe {
             rizin_sys::rz_analysis_op(
                 self.0.as_ref().analysis,
                 addr_of_mut!(op.0),
@@ -97,56 +106,14 @@ impl<'a> RzCore {
                 bytes.len() as _,
                 mask,
             )
-        };
+        });
         if res <= 0 {
-            Err(anyhow!("failed analysis op"))
+            Err(Error::DecodeFailed {
+                addr: addr as u64,
+                log,
+            })
         } else {
             Ok(op)
         }
     }
-
-    pub fn config_set(&self, k: &str, v: &str) -> anyhow::Result<&Self> {
-        let node = unsafe {
-            rizin_sys::rz_config_set(
-                self.0.as_ref().config,
-                CString::new(k)?.as_ptr(),
-                CString::new(v)?.as_ptr(),
-            )
-        };
-        NonNull::new(node)
-            .map(|_| self)
-            .ok_or(anyhow!("{} is null", k))
-    }
-
-    pub fn config_get(&self, key: &str) -> Option<&str> {
-        unsafe {
-            CString::from_str(key)
-                .map(|ckey| {
-                    let ptr = rizin_sys::rz_config_get(self.0.as_ref().config, ckey.as_ptr());
-                    if ptr.is_null() {
-                        None
-                    } else {
-                        Some(CStr::from_ptr(ptr).to_str().expect("invalid utf8"))
-                    }
-                })
-                .ok()
-                .flatten()
-        }
-    }
-
-    fn bin_open(&'a mut self, path: PathBuf) -> anyhow::Result<RzBinFile<'a>> {
-        let mut opt = rizin_sys::RzBinOptions::default();
-        unsafe {
-            rizin_sys::rz_bin_options_init(addr_of_mut!(opt), 0, 0, 0, false);
-        }
-        let cpath = CString::new(path.to_str().unwrap())?;
-        let bf = unsafe {
-            rizin_sys::rz_bin_open(self.0.as_ref().bin, cpath.as_ptr(), addr_of_mut!(opt))
-        };
-        let bf = RzBinFile {
-            core: self,
-            inner: NonNull::new(bf).ok_or(anyhow!("failed open {}", path.to_str().unwrap()))?,
-        };
-        Ok(bf)
-    }
 }
@@ -0,0 +1,149 @@
+use crate::RzCore;
+use crate::error::{Error, Result, with_log};
+use crate::util::{RzPVector, cstr_to_string, raw_enum};
+use std::ffi::CString;
+use std::ptr::null;
+
+raw_enum!(
+    MetaKind: rizin_sys::RzAnalysisMetaType {
+        Data = RZ_META_TYPE_DATA,
+        Code = RZ_META_TYPE_CODE,
+        String = RZ_META_TYPE_STRING,
+        Format = RZ_META_TYPE_FORMAT,
+        Magic = RZ_META_TYPE_MAGIC,
+        Hide = RZ_META_TYPE_HIDE,
+        Comment = RZ_META_TYPE_COMMENT,
+        Run = RZ_META_TYPE_RUN,
+        Highlight = RZ_META_TYPE_HIGHLIGHT,
+        VarType = RZ_META_TYPE_VARTYPE,
+    }
+);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaItem {
+    pub kind: MetaKind,
+    pub addr: u64,
+    pub size: u64,
+    /// Kind specific detail, e.g. the string encoding or data word size.
+    pub subtype: i32,
+    /// Comment text, format string, or string contents.
+    pub text: Option<String>,
+    pub space: Option<String>,
+}
+
+impl MetaItem {
+    fn new(item: &rizin_sys::RzAnalysisMetaItem, addr: u64, size: u64) -> Self {
+        Self {
+            kind: item.type_.into(),
+            addr,
+            size,
+            subtype: item.subtype,
+            text: cstr_to_string(item.str_),
+            space: unsafe { item.space.as_ref() }.and_then(|s| cstr_to_string(s.name)),
+        }
+    }
+}
+
+fn kind_or_any(kind: Option<MetaKind>) -> rizin_sys::RzAnalysisMetaType {
+    kind.map_or(rizin_sys::RZ_META_TYPE_ANY, Into::into)
+}
+
+impl RzCore {
+    /// Sets a metadata item of `size` bytes at `addr`, replacing one of the same kind.
+    pub fn set_meta(&self, kind: MetaKind, addr: u64, size: u64, text: Option<&str>) -> Result<()> {
+        let ctext = text.map(CString::new).transpose()?;
+        let (ok, log) = with_log(|| unsafe {
+            rizin_sys::rz_meta_set(
+                self.analysis(),
+                kind.into(),
+                addr,
+                size,
+                ctext.as_ref().map_or(null(), |t| t.as_ptr()),
+            )
+        });
+        if ok {
+            Ok(())
+        } else {
+            Err(Error::rizin(
+                format!("set meta {:?} at {:#x}", kind, addr),
+                log,
+            ))
+        }
+    }
+
+    pub fn meta_at(&self, addr: u64, kind: Option<MetaKind>) -> Option<MetaItem> {
+        let mut size = 0u64;
+        unsafe {
+            rizin_sys::rz_meta_get_at(self.analysis(), addr, kind_or_any(kind), &mut size).as_ref()
+        }
+        .map(|item| MetaItem::new(item, addr, size))
+    }
+
+    /// Items intersecting `[start, start + size)`, optionally of one kind only.
+    pub fn meta_in(&self, start: u64, size: u64, kind: Option<MetaKind>) -> Vec<MetaItem> {
+        RzPVector::<rizin_sys::RzIntervalNode>::from_raw(unsafe {
+            rizin_sys::rz_meta_get_all_intersect(self.analysis(), start, size, kind_or_any(kind))
+        })
+        .map(|nodes| {
+            nodes
+                .iter()
+                .filter_map(|node| unsafe { node.as_ref() })
+                .filter_map(|node| {
+                    unsafe { (node.data as *const rizin_sys::RzAnalysisMetaItem).as_ref() }
+                        .map(|item| MetaItem::new(item, node.start, node.end - node.start + 1))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn delete_meta(&self, kind: Option<MetaKind>, addr: u64, size: u64) {
+        unsafe { rizin_sys::rz_meta_del(self.analysis(), kind_or_any(kind), addr, size) };
+    }
+
+    pub fn set_comment(&self, addr: u64, comment: &str) -> Result<()> {
+        self.set_meta(MetaKind::Comment, addr, 1, Some(comment))
+    }
+
+    pub fn comment(&self, addr: u64) -> Option<String> {
+        cstr_to_string(unsafe {
+            rizin_sys::rz_meta_get_string(self.analysis(), rizin_sys::RZ_META_TYPE_COMMENT, addr)
+        })
+    }
+
+    pub fn delete_comment(&self, addr: u64) {
+        self.delete_meta(Some(MetaKind::Comment), addr, 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RzCore;
+    use crate::meta::MetaKind;
+
+    #[test]
+    fn test_meta() {
+        let core = RzCore::new();
+        core.set_comment(0x1000, "entry").unwrap();
+        assert_eq!(core.comment(0x1000).as_deref(), Some("entry"));
+
+        core.set_meta(MetaKind::Data, 0x2000, 8, None).unwrap();
+        core.set_meta(MetaKind::String, 0x2010, 6, Some("hello"))
+            .unwrap();
+        let data = core.meta_at(0x2000, Some(MetaKind::Data)).unwrap();
+        assert_eq!(data.size, 8);
+
+        let items = core.meta_in(0x2000, 0x100, None);
+        assert_eq!(items.len(), 2);
+        let string = items.iter().find(|m| m.kind == MetaKind::String).unwrap();
+        assert_eq!(
+            (string.addr, string.size, string.text.as_deref()),
+            (0x2010, 6, Some("hello"))
+        );
+
+        core.delete_comment(0x1000);
+        assert!(core.comment(0x1000).is_none());
+        core.delete_meta(None, 0x2000, 0x100);
+        assert!(core.meta_in(0x2000, 0x100, None).is_empty());
+    }
+}
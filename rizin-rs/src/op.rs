@@ -0,0 +1,407 @@
+use crate::analysis::addr_opt;
+use crate::error::{Error, Result};
+use crate::il::IlEffect;
+use crate::util::{RzList, RzPVector, RzStrBuf, cstr_to_string, raw_enum};
+use std::ffi::CStr;
+use std::mem::ManuallyDrop;
+use std::ptr::{addr_of, addr_of_mut};
+
+raw_enum!(
+    /// Instruction kind. Conditional, indirect and register variants are distinct values.
+    OpType: rizin_sys::RzAnalysisOpType {
+        Null = RZ_ANALYSIS_OP_TYPE_NULL,
+        Jmp = RZ_ANALYSIS_OP_TYPE_JMP,
+        Ujmp = RZ_ANALYSIS_OP_TYPE_UJMP,
+        Rjmp = RZ_ANALYSIS_OP_TYPE_RJMP,
+        Ijmp = RZ_ANALYSIS_OP_TYPE_IJMP,
+        Irjmp = RZ_ANALYSIS_OP_TYPE_IRJMP,
+        Cjmp = RZ_ANALYSIS_OP_TYPE_CJMP,
+        Mjmp = RZ_ANALYSIS_OP_TYPE_MJMP,
+        Ucjmp = RZ_ANALYSIS_OP_TYPE_UCJMP,
+        Call = RZ_ANALYSIS_OP_TYPE_CALL,
+        Ucall = RZ_ANALYSIS_OP_TYPE_UCALL,
+        Rcall = RZ_ANALYSIS_OP_TYPE_RCALL,
+        Icall = RZ_ANALYSIS_OP_TYPE_ICALL,
+        Ircall = RZ_ANALYSIS_OP_TYPE_IRCALL,
+        Ccall = RZ_ANALYSIS_OP_TYPE_CCALL,
+        Uccall = RZ_ANALYSIS_OP_TYPE_UCCALL,
+        Ret = RZ_ANALYSIS_OP_TYPE_RET,
+        Cret = RZ_ANALYSIS_OP_TYPE_CRET,
+        Ill = RZ_ANALYSIS_OP_TYPE_ILL,
+        Unk = RZ_ANALYSIS_OP_TYPE_UNK,
+        Nop = RZ_ANALYSIS_OP_TYPE_NOP,
+        Mov = RZ_ANALYSIS_OP_TYPE_MOV,
+        Cmov = RZ_ANALYSIS_OP_TYPE_CMOV,
+        Trap = RZ_ANALYSIS_OP_TYPE_TRAP,
+        Swi = RZ_ANALYSIS_OP_TYPE_SWI,
+        Cswi = RZ_ANALYSIS_OP_TYPE_CSWI,
+        Upush = RZ_ANALYSIS_OP_TYPE_UPUSH,
+        Rpush = RZ_ANALYSIS_OP_TYPE_RPUSH,
+        Push = RZ_ANALYSIS_OP_TYPE_PUSH,
+        Pop = RZ_ANALYSIS_OP_TYPE_POP,
+        Cmp = RZ_ANALYSIS_OP_TYPE_CMP,
+        Acmp = RZ_ANALYSIS_OP_TYPE_ACMP,
+        Add = RZ_ANALYSIS_OP_TYPE_ADD,
+        Sub = RZ_ANALYSIS_OP_TYPE_SUB,
+        Io = RZ_ANALYSIS_OP_TYPE_IO,
+        Mul = RZ_ANALYSIS_OP_TYPE_MUL,
+        Div = RZ_ANALYSIS_OP_TYPE_DIV,
+        Shr = RZ_ANALYSIS_OP_TYPE_SHR,
+        Shl = RZ_ANALYSIS_OP_TYPE_SHL,
+        Sal = RZ_ANALYSIS_OP_TYPE_SAL,
+        Sar = RZ_ANALYSIS_OP_TYPE_SAR,
+        Or = RZ_ANALYSIS_OP_TYPE_OR,
+        And = RZ_ANALYSIS_OP_TYPE_AND,
+        Xor = RZ_ANALYSIS_OP_TYPE_XOR,
+        Nor = RZ_ANALYSIS_OP_TYPE_NOR,
+        Not = RZ_ANALYSIS_OP_TYPE_NOT,
+        Store = RZ_ANALYSIS_OP_TYPE_STORE,
+        Load = RZ_ANALYSIS_OP_TYPE_LOAD,
+        Lea = RZ_ANALYSIS_OP_TYPE_LEA,
+        Leave = RZ_ANALYSIS_OP_TYPE_LEAVE,
+        Ror = RZ_ANALYSIS_OP_TYPE_ROR,
+        Rol = RZ_ANALYSIS_OP_TYPE_ROL,
+        Xchg = RZ_ANALYSIS_OP_TYPE_XCHG,
+        Mod = RZ_ANALYSIS_OP_TYPE_MOD,
+        Switch = RZ_ANALYSIS_OP_TYPE_SWITCH,
+        Case = RZ_ANALYSIS_OP_TYPE_CASE,
+        Length = RZ_ANALYSIS_OP_TYPE_LENGTH,
+        Cast = RZ_ANALYSIS_OP_TYPE_CAST,
+        New = RZ_ANALYSIS_OP_TYPE_NEW,
+        Abs = RZ_ANALYSIS_OP_TYPE_ABS,
+        Cpl = RZ_ANALYSIS_OP_TYPE_CPL,
+        Crypto = RZ_ANALYSIS_OP_TYPE_CRYPTO,
+        Sync = RZ_ANALYSIS_OP_TYPE_SYNC,
+    }
+);
+
+impl OpType {
+    pub fn is_jump(self) -> bool {
+        matches!(
+            self,
+            OpType::Jmp
+                | OpType::Ujmp
+                | OpType::Rjmp
+                | OpType::Ijmp
+                | OpType::Irjmp
+                | OpType::Cjmp
+                | OpType::Mjmp
+                | OpType::Ucjmp
+        )
+    }
+
+    pub fn is_call(self) -> bool {
+        matches!(
+            self,
+            OpType::Call
+                | OpType::Ucall
+                | OpType::Rcall
+                | OpType::Icall
+                | OpType::Ircall
+                | OpType::Ccall
+                | OpType::Uccall
+        )
+    }
+
+    pub fn is_return(self) -> bool {
+        matches!(self, OpType::Ret | OpType::Cret)
+    }
+
+    pub fn is_conditional(self) -> bool {
+        rizin_sys::RzAnalysisOpType::from(self) & rizin_sys::RZ_ANALYSIS_OP_TYPE_COND != 0
+    }
+}
+
+raw_enum!(
+    /// Condition under which a conditional op is taken.
+    Cond: rizin_sys::RzTypeCond {
+        Al = RZ_TYPE_COND_AL,
+        Eq = RZ_TYPE_COND_EQ,
+        Ne = RZ_TYPE_COND_NE,
+        Ge = RZ_TYPE_COND_GE,
+        Gt = RZ_TYPE_COND_GT,
+        Le = RZ_TYPE_COND_LE,
+        Lt = RZ_TYPE_COND_LT,
+        Nv = RZ_TYPE_COND_NV,
+        Hs = RZ_TYPE_COND_HS,
+        Lo = RZ_TYPE_COND_LO,
+        Mi = RZ_TYPE_COND_MI,
+        Pl = RZ_TYPE_COND_PL,
+        Vs = RZ_TYPE_COND_VS,
+        Vc = RZ_TYPE_COND_VC,
+        Hi = RZ_TYPE_COND_HI,
+        Ls = RZ_TYPE_COND_LS,
+    }
+);
+
+raw_enum!(
+    OpFamily: rizin_sys::RzAnalysisOpFamily {
+        Unknown = RZ_ANALYSIS_OP_FAMILY_UNKNOWN,
+        Cpu = RZ_ANALYSIS_OP_FAMILY_CPU,
+        Fpu = RZ_ANALYSIS_OP_FAMILY_FPU,
+        Mmx = RZ_ANALYSIS_OP_FAMILY_MMX,
+        Sse = RZ_ANALYSIS_OP_FAMILY_SSE,
+        Priv = RZ_ANALYSIS_OP_FAMILY_PRIV,
+        Crypto = RZ_ANALYSIS_OP_FAMILY_CRYPTO,
+        Thread = RZ_ANALYSIS_OP_FAMILY_THREAD,
+        Virt = RZ_ANALYSIS_OP_FAMILY_VIRT,
+        Security = RZ_ANALYSIS_OP_FAMILY_SECURITY,
+        Io = RZ_ANALYSIS_OP_FAMILY_IO,
+    }
+);
+
+raw_enum!(
+    StackOp: rizin_sys::RzAnalysisStackOp {
+        Null = RZ_ANALYSIS_STACK_NULL,
+        Nop = RZ_ANALYSIS_STACK_NOP,
+        Inc = RZ_ANALYSIS_STACK_INC,
+        Get = RZ_ANALYSIS_STACK_GET,
+        Set = RZ_ANALYSIS_STACK_SET,
+        Reset = RZ_ANALYSIS_STACK_RESET,
+    }
+);
+
+raw_enum!(
+    ValueKind: rizin_sys::RzAnalysisValueType {
+        Reg = RZ_ANALYSIS_VAL_REG,
+        Mem = RZ_ANALYSIS_VAL_MEM,
+        Imm = RZ_ANALYSIS_VAL_IMM,
+        Unknown = RZ_ANALYSIS_VAL_UNK,
+    }
+);
+
+/// Instruction prefix flags such as `rep` or `lock`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct OpPrefix(pub u32);
+
+impl OpPrefix {
+    pub const NONE: OpPrefix = OpPrefix(0);
+    pub const COND: OpPrefix = OpPrefix(rizin_sys::RZ_ANALYSIS_OP_PREFIX_COND);
+    pub const REP: OpPrefix = OpPrefix(rizin_sys::RZ_ANALYSIS_OP_PREFIX_REP);
+    pub const REPNE: OpPrefix = OpPrefix(rizin_sys::RZ_ANALYSIS_OP_PREFIX_REPNE);
+    pub const LOCK: OpPrefix = OpPrefix(rizin_sys::RZ_ANALYSIS_OP_PREFIX_LOCK);
+    pub const LIKELY: OpPrefix = OpPrefix(rizin_sys::RZ_ANALYSIS_OP_PREFIX_LIKELY);
+    pub const UNLIKELY: OpPrefix = OpPrefix(rizin_sys::RZ_ANALYSIS_OP_PREFIX_UNLIKELY);
+
+    pub fn contains(self, other: OpPrefix) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+/// Operand of an op: `[base + reg * mul + delta]` for memory, a register, or an immediate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Value {
+    pub kind: ValueKind,
+    pub read: bool,
+    pub write: bool,
+    pub absolute: bool,
+    pub memref: i32,
+    pub base: u64,
+    pub delta: i64,
+    pub imm: i64,
+    pub mul: i32,
+    pub reg: Option<String>,
+    pub regdelta: Option<String>,
+}
+
+impl From<&rizin_sys::RzAnalysisValue> for Value {
+    fn from(v: &rizin_sys::RzAnalysisValue) -> Self {
+        Self {
+            kind: v.type_.into(),
+            read: v.access & rizin_sys::RZ_ANALYSIS_ACC_R != 0,
+            write: v.access & rizin_sys::RZ_ANALYSIS_ACC_W != 0,
+            absolute: v.absolute != 0,
+            memref: v.memref,
+            base: v.base,
+            delta: v.delta,
+            imm: v.imm,
+            mul: v.mul,
+            reg: cstr_to_string(v.reg),
+            regdelta: cstr_to_string(v.regdelta),
+        }
+    }
+}
+
+pub struct RzAnalysisOp(pub(crate) rizin_sys::RzAnalysisOp);
+
+impl Drop for RzAnalysisOp {
+    fn drop(&mut self) {
+        unsafe {
+            rizin_sys::rz_analysis_op_fini(addr_of_mut!(self.0));
+        }
+    }
+}
+
+impl RzAnalysisOp {
+    /// The op stays owned by `self`; the pointer is valid while it is.
+    pub fn as_ptr(&self) -> *const rizin_sys::RzAnalysisOp {
+        &self.0
+    }
+
+    /// Fields not wrapped by the accessors below.
+    pub fn as_raw(&self) -> &rizin_sys::RzAnalysisOp {
+        &self.0
+    }
+
+    pub fn mnemonic(&self) -> Result<&str> {
+        if self.0.mnemonic.is_null() {
+            Err(Error::NullPointer("mnemonic"))
+        } else {
+            let cstr = unsafe { CStr::from_ptr(self.0.mnemonic) };
+            Ok(cstr.to_str()?)
+        }
+    }
+
+    pub fn addr(&self) -> u64 {
+        self.0.addr
+    }
+
+    pub fn size(&self) -> usize {
+        self.0.size.max(0) as usize
+    }
+
+    pub fn op_type(&self) -> OpType {
+        self.0.type_.into()
+    }
+
+    /// Secondary type, e.g. the memory access kind of a `mov`.
+    pub fn op_type2(&self) -> OpType {
+        self.0.type2.into()
+    }
+
+    pub fn cond(&self) -> Cond {
+        self.0.cond.into()
+    }
+
+    pub fn family(&self) -> OpFamily {
+        self.0.family.into()
+    }
+
+    pub fn prefix(&self) -> OpPrefix {
+        OpPrefix(self.0.prefix)
+    }
+
+    pub fn jump(&self) -> Option<u64> {
+        addr_opt(self.0.jump)
+    }
+
+    pub fn fail(&self) -> Option<u64> {
+        addr_opt(self.0.fail)
+    }
+
+    pub fn ptr(&self) -> Option<u64> {
+        addr_opt(self.0.ptr)
+    }
+
+    pub fn val(&self) -> Option<u64> {
+        addr_opt(self.0.val)
+    }
+
+    pub fn ptr_size(&self) -> usize {
+        self.0.ptrsize.max(0) as usize
+    }
+
+    /// Number of delay slots following a branch.
+    pub fn delay(&self) -> usize {
+        self.0.delay.max(0) as usize
+    }
+
+    pub fn is_eob(&self) -> bool {
+        self.0.eob
+    }
+
+    pub fn stack_op(&self) -> StackOp {
+        self.0.stackop.into()
+    }
+
+    /// Stack pointer change for ops that increment it.
+    pub fn stack_delta(&self) -> Option<i64> {
+        (self.stack_op() == StackOp::Inc).then_some(self.0.stackptr)
+    }
+
+    pub fn srcs(&self) -> Vec<Value> {
+        Self::values(addr_of!(self.0.srcs))
+    }
+
+    pub fn dsts(&self) -> Vec<Value> {
+        Self::values(addr_of!(self.0.dsts))
+    }
+
+    fn values(vec: *const rizin_sys::RzPVector) -> Vec<Value> {
+        RzPVector::<rizin_sys::RzAnalysisValue>::from_raw(vec as *mut _)
+            .map(ManuallyDrop::new)
+            .map(|vals| {
+                vals.iter()
+                    .filter_map(|v| unsafe { v.as_ref() })
+                    .map(Value::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Registers read or written by the op; needs `RZ_ANALYSIS_OP_MASK_VAL`.
+    pub fn accessed_regs(&self) -> Vec<Value> {
+        RzList::<rizin_sys::RzAnalysisValue>::from_raw(self.0.access)
+            .map(ManuallyDrop::new)
+            .map(|vals| vals.iter().map(Value::from).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn il_op(&self) -> Option<rizin_sys::RzAnalysisLiftedILOp> {
+        if self.0.il_op.is_null() {
+            None
+        } else {
+            Some(self.0.il_op)
+        }
+    }
+
+    pub fn il(&self) -> Option<IlEffect> {
+        self.il_op()
+            .and_then(|op| unsafe { IlEffect::from_raw(op) })
+    }
+
+    pub fn il_str(&self, pretty: bool) -> Option<String> {
+        self.il_op().map(|op| {
+            let mut sb = RzStrBuf::new();
+            unsafe {
+                rizin_sys::rz_il_op_effect_stringify(op, addr_of_mut!(sb.0), pretty);
+            }
+            sb.to_string()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RzCore;
+    use crate::op::{OpType, StackOp};
+
+    #[test]
+    fn test_op_accessors() {
+        let core = RzCore::new();
+        core.config_set("asm.arch", "x86").unwrap();
+        core.config_set("asm.bits", "64").unwrap();
+        let mask = rizin_sys::RZ_ANALYSIS_OP_MASK_BASIC | rizin_sys::RZ_ANALYSIS_OP_MASK_VAL;
+
+        let jmp = core.analysis_op(&[0xeb, 0x10], 0x1000, mask).unwrap();
+        assert_eq!(jmp.op_type(), OpType::Jmp);
+        assert!(jmp.op_type().is_jump());
+        assert_eq!(jmp.size(), 2);
+        assert_eq!(jmp.jump(), Some(0x1012));
+
+        let jz = core.analysis_op(&[0x74, 0x10], 0x1000, mask).unwrap();
+        assert_eq!(jz.op_type(), OpType::Cjmp);
+        assert!(jz.op_type().is_conditional());
+        assert_eq!(jz.fail(), Some(0x1002));
+
+        let push = core.analysis_op(&[0x55], 0x1000, mask).unwrap();
+        assert_eq!(push.op_type(), OpType::Rpush);
+        assert_eq!(push.stack_op(), StackOp::Inc);
+        assert_eq!(push.stack_delta(), Some(8));
+
+        let add = core.analysis_op(&[0x48, 0x01, 0xd8], 0x1000, mask).unwrap();
+        assert_eq!(add.op_type(), OpType::Add);
+        assert_eq!(add.dsts()[0].reg.as_deref(), Some("rax"));
+        assert_eq!(add.srcs()[0].reg.as_deref(), Some("rbx"));
+    }
+}
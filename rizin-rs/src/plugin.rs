@@ -0,0 +1,118 @@
+pub mod arch;
+pub mod bin;
+pub mod io;
+
+use std::any::TypeId;
+use std::cell::UnsafeCell;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString, c_char, c_void};
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr::null;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+/// Plugin metadata lives as long as the process, rizin keeps raw pointers to it.
+pub(crate) fn leak_cstr(s: &str) -> *const c_char {
+    CString::new(s).expect("nul in plugin string").into_raw()
+}
+
+/// Copies a string into memory rizin is allowed to free.
+pub(crate) fn rz_strdup(s: &str) -> *mut c_char {
+    unsafe { rizin_sys::rz_str_ndup(s.as_ptr() as _, s.len() as _) }
+}
+
+/// Logs through rizin, so the error is attached to the call that failed.
+pub(crate) fn log_error(plugin: &CStr, msg: &str) {
+    let msg = CString::new(msg.replace('\0', "\\0")).unwrap_or_default();
+    unsafe {
+        rizin_sys::rz_log(
+            c"plugin".as_ptr(),
+            concat!(file!(), "\0").as_ptr() as _,
+            line!(),
+            rizin_sys::RZ_LOGLVL_ERROR,
+            null(),
+            c"%s: %s".as_ptr(),
+            plugin.as_ptr(),
+            msg.as_ptr(),
+        )
+    };
+}
+
+/// Runs plugin code called from C. Unwinding into rizin's frames aborts the
+/// process, so a panic is logged and becomes `None`.
+pub(crate) fn catch_panic<R>(plugin: &CStr, f: impl FnOnce() -> R) -> Option<R> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|_| log_error(plugin, "panicked"))
+        .ok()
+}
+
+/// Returns the single vtable built for plugin type `P`, creating it on first use
+/// so every core and every descriptor share it.
+pub(crate) fn plugin_for<P: 'static, T: 'static>(make: impl FnOnce() -> T) -> *mut T {
+    static PLUGINS: OnceLock<Mutex<HashMap<TypeId, usize>>> = OnceLock::new();
+    let mut plugins = PLUGINS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    *plugins
+        .entry(TypeId::of::<(P, T)>())
+        .or_insert_with(|| Box::into_raw(Box::new(make())) as usize) as *mut T
+}
+
+/// Plugin table in a static. rizin takes `*mut` pointers to plugins, so the
+/// value lives in writable memory.
+#[repr(transparent)]
+pub struct PluginDef<T>(UnsafeCell<T>);
+
+unsafe impl<T> Sync for PluginDef<T> {}
+
+impl<T> PluginDef<T> {
+    pub const fn new(value: T) -> Self {
+        Self(UnsafeCell::new(value))
+    }
+
+    pub const fn as_ptr(&self) -> *mut T {
+        self.0.get()
+    }
+}
+
+pub const fn lib_struct(kind: rizin_sys::RzLibType, data: *mut c_void) -> rizin_sys::RzLibStruct {
+    let mut s: rizin_sys::RzLibStruct = unsafe { mem::zeroed() };
+    s.type_ = kind;
+    s.data = data;
+    s.version = rizin_sys::RZ_VERSION.as_ptr() as _;
+    s
+}
+
+/// Exports a plugin from a `cdylib` as the `rizin_plugin` symbol read by `rz_lib`.
+/// A library carries one plugin, so the analysis and asm halves of an
+/// [`ArchPlugin`](arch::ArchPlugin) are built as separate libraries:
+///
+/// ```ignore
+/// rizin_rs::rizin_plugin!(analysis: MyDsp);
+/// rizin_rs::rizin_plugin!(bin: MyFirmware);
+/// ```
+#[macro_export]
+macro_rules! rizin_plugin {
+    (analysis: $ty:ty) => {
+        $crate::rizin_plugin!(@export RzAnalysisPlugin, arch::analysis_plugin, RZ_LIB_TYPE_ANALYSIS, $ty);
+    };
+    (asm: $ty:ty) => {
+        $crate::rizin_plugin!(@export RzAsmPlugin, arch::asm_plugin, RZ_LIB_TYPE_ASM, $ty);
+    };
+    (bin: $ty:ty) => {
+        $crate::rizin_plugin!(@export RzBinPlugin, bin::bin_plugin, RZ_LIB_TYPE_BIN, $ty);
+    };
+    (@export $plugin:ident, $module:ident :: $make:ident, $kind:ident, $ty:ty) => {
+        static RIZIN_PLUGIN_DEF: $crate::plugin::PluginDef<$crate::rizin_sys::$plugin> =
+            $crate::plugin::PluginDef::new($crate::plugin::$module::$make::<$ty>());
+
+        #[unsafe(no_mangle)]
+        #[allow(non_upper_case_globals)]
+        pub static rizin_plugin: $crate::plugin::PluginDef<$crate::rizin_sys::RzLibStruct> =
+            $crate::plugin::PluginDef::new($crate::plugin::lib_struct(
+                $crate::rizin_sys::$kind,
+                RIZIN_PLUGIN_DEF.as_ptr() as _,
+            ));
+    };
+}
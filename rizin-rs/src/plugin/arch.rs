@@ -0,0 +1,358 @@
+use crate::RzCore;
+use crate::error::{Error, Result};
+use crate::il::IlEffect;
+use crate::op::{Cond, OpFamily, OpType, StackOp};
+use crate::plugin::{catch_panic, log_error, plugin_for, rz_strdup};
+use std::ffi::{CStr, CString, c_char, c_int, c_void};
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::null_mut;
+use std::slice;
+
+/// Result of decoding one instruction, copied into the `RzAnalysisOp` rizin asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedOp {
+    pub size: usize,
+    pub mnemonic: String,
+    pub op_type: OpType,
+    pub cond: Cond,
+    pub family: OpFamily,
+    pub jump: Option<u64>,
+    pub fail: Option<u64>,
+    pub ptr: Option<u64>,
+    pub val: Option<u64>,
+    pub stack_op: StackOp,
+    pub stack_delta: i64,
+    /// Number of delay slots following a branch.
+    pub delay: usize,
+}
+
+impl Default for DecodedOp {
+    fn default() -> Self {
+        Self {
+            size: 0,
+            mnemonic: String::new(),
+            op_type: OpType::Unk,
+            cond: Cond::Al,
+            family: OpFamily::Cpu,
+            jump: None,
+            fail: None,
+            ptr: None,
+            val: None,
+            stack_op: StackOp::Null,
+            stack_delta: 0,
+            delay: 0,
+        }
+    }
+}
+
+/// Parameters of the IL VM rizin creates for the architecture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IlConfig {
+    pub pc_size: u32,
+    pub big_endian: bool,
+    pub mem_key_size: u32,
+}
+
+/// Instruction set implemented in Rust, exposed to rizin as an analysis and asm
+/// plugin pair. Every `RzAnalysis` and `RzAsm` using the plugin gets its own value.
+///
+/// Strings are `CStr` so the plugin tables can be built at compile time for
+/// [`rizin_plugin!`](crate::rizin_plugin).
+pub trait ArchPlugin: Sized + 'static {
+    /// Name selected with `asm.arch`.
+    const NAME: &'static CStr;
+    const DESC: &'static CStr = c"";
+    const AUTHOR: &'static CStr = c"";
+    /// Supported `asm.bits` values, each one of 8, 16, 32 or 64.
+    const BITS: &'static [u32];
+    const BIG_ENDIAN: bool = false;
+
+    fn new() -> Self;
+
+    /// Decodes the instruction at the start of `bytes`, `None` if it is invalid.
+    fn decode(&self, bytes: &[u8], addr: u64, bits: u32) -> Option<DecodedOp>;
+
+    fn reg_profile(&self, bits: u32) -> String;
+
+    /// Lifts a decoded instruction, only called when rizin asks for IL.
+    fn lift(&self, _op: &DecodedOp, _bytes: &[u8], _addr: u64) -> Option<IlEffect> {
+        None
+    }
+
+    /// Enables the IL VM for the architecture; `lift` should be implemented too.
+    fn il_config(&self, _bits: u32) -> Option<IlConfig> {
+        None
+    }
+}
+
+/// `RZ_SYS_BITS_*` mask, where each flag happens to be the width in bytes.
+const fn bits_mask(bits: &[u32]) -> c_int {
+    let mut mask = 0;
+    let mut i = 0;
+    while i < bits.len() {
+        mask |= (bits[i] / 8) as c_int;
+        i += 1;
+    }
+    mask
+}
+
+pub const fn analysis_plugin<P: ArchPlugin>() -> rizin_sys::RzAnalysisPlugin {
+    let mut p: rizin_sys::RzAnalysisPlugin = unsafe { mem::zeroed() };
+    p.name = P::NAME.as_ptr() as _;
+    p.arch = P::NAME.as_ptr() as _;
+    p.desc = P::DESC.as_ptr() as _;
+    p.author = P::AUTHOR.as_ptr() as _;
+    p.bits = bits_mask(P::BITS);
+    p.init = Some(Trampolines::<P>::init);
+    p.fini = Some(Trampolines::<P>::fini);
+    p.op = Some(Trampolines::<P>::op);
+    p.get_reg_profile = Some(Trampolines::<P>::get_reg_profile);
+    p.il_config = Some(Trampolines::<P>::il_config);
+    p
+}
+
+pub const fn asm_plugin<P: ArchPlugin>() -> rizin_sys::RzAsmPlugin {
+    let mut p: rizin_sys::RzAsmPlugin = unsafe { mem::zeroed() };
+    p.name = P::NAME.as_ptr() as _;
+    p.arch = P::NAME.as_ptr() as _;
+    p.desc = P::DESC.as_ptr() as _;
+    p.author = P::AUTHOR.as_ptr() as _;
+    p.bits = bits_mask(P::BITS);
+    p.endian = if P::BIG_ENDIAN {
+        rizin_sys::RZ_SYS_ENDIAN_BIG
+    } else {
+        rizin_sys::RZ_SYS_ENDIAN_LITTLE
+    } as _;
+    p.init = Some(Trampolines::<P>::init);
+    p.fini = Some(Trampolines::<P>::fini);
+    p.disassemble = Some(Trampolines::<P>::disassemble);
+    p
+}
+
+struct Trampolines<P>(PhantomData<P>);
+
+impl<P: ArchPlugin> Trampolines<P> {
+    unsafe fn state<'a>(data: *mut c_void) -> Option<&'a P> {
+        unsafe { (data as *const P).as_ref() }
+    }
+
+    unsafe extern "C" fn init(user: *mut *mut c_void) -> bool {
+        let Some(state) = catch_panic(P::NAME, P::new) else {
+            return false;
+        };
+        unsafe { *user = Box::into_raw(Box::new(state)) as _ };
+        true
+    }
+
+    unsafe extern "C" fn fini(user: *mut c_void) -> bool {
+        if !user.is_null() {
+            let state = unsafe { Box::from_raw(user as *mut P) };
+            catch_panic(P::NAME, || drop(state));
+        }
+        true
+    }
+
+    unsafe extern "C" fn op(
+        a: *mut rizin_sys::RzAnalysis,
+        op: *mut rizin_sys::RzAnalysisOp,
+        addr: u64,
+        data: *const u8,
+        len: c_int,
+        mask: rizin_sys::RzAnalysisOpMask,
+    ) -> c_int {
+        let (Some(a), Some(op)) = (unsafe { a.as_ref() }, unsafe { op.as_mut() }) else {
+            return -1;
+        };
+        let Some(state) = (unsafe { Self::state(a.plugin_data) }) else {
+            return -1;
+        };
+        let bytes = unsafe { slice::from_raw_parts(data, len.max(0) as _) };
+        let Some(Some(decoded)) = catch_panic(P::NAME, || state.decode(bytes, addr, a.bits as _))
+        else {
+            op.type_ = rizin_sys::RZ_ANALYSIS_OP_TYPE_ILL;
+            return -1;
+        };
+        op.addr = addr;
+        op.size = decoded.size as _;
+        op.type_ = decoded.op_type.into();
+        op.cond = decoded.cond.into();
+        op.family = decoded.family.into();
+        op.jump = decoded.jump.unwrap_or(u64::MAX);
+        op.fail = decoded.fail.unwrap_or(u64::MAX);
+        op.ptr = decoded.ptr.unwrap_or(u64::MAX) as _;
+        op.val = decoded.val.unwrap_or(u64::MAX) as _;
+        op.stackop = decoded.stack_op.into();
+        op.stackptr = decoded.stack_delta;
+        op.delay = decoded.delay as _;
+        if mask & rizin_sys::RZ_ANALYSIS_OP_MASK_DISASM != 0 {
+            op.mnemonic = rz_strdup(&decoded.mnemonic);
+        }
+        if mask & rizin_sys::RZ_ANALYSIS_OP_MASK_IL != 0 {
+            let bytes = &bytes[..decoded.size.min(bytes.len())];
+            if let Some(il) = catch_panic(P::NAME, || state.lift(&decoded, bytes, addr)).flatten() {
+                match il.to_raw() {
+                    Ok(il) => op.il_op = il,
+                    Err(e) => log_error(P::NAME, &e.to_string()),
+                }
+            }
+        }
+        decoded.size as _
+    }
+
+    unsafe extern "C" fn get_reg_profile(a: *mut rizin_sys::RzAnalysis) -> *mut c_char {
+        let Some(a) = (unsafe { a.as_ref() }) else {
+            return null_mut();
+        };
+        unsafe { Self::state(a.plugin_data) }
+            .and_then(|state| catch_panic(P::NAME, || state.reg_profile(a.bits as _)))
+            .map_or(null_mut(), |profile| rz_strdup(&profile))
+    }
+
+    unsafe extern "C" fn il_config(
+        a: *mut rizin_sys::RzAnalysis,
+    ) -> *mut rizin_sys::RzAnalysisILConfig {
+        let Some(a) = (unsafe { a.as_ref() }) else {
+            return null_mut();
+        };
+        let config = unsafe { Self::state(a.plugin_data) }
+            .and_then(|s| catch_panic(P::NAME, || s.il_config(a.bits as _)).flatten());
+        match config {
+            Some(cfg) => unsafe {
+                rizin_sys::rz_analysis_il_config_new(cfg.pc_size, cfg.big_endian, cfg.mem_key_size)
+            },
+            None => null_mut(),
+        }
+    }
+
+    unsafe extern "C" fn disassemble(
+        a: *mut rizin_sys::RzAsm,
+        op: *mut rizin_sys::RzAsmOp,
+        buf: *const u8,
+        len: c_int,
+    ) -> c_int {
+        let Some(a) = (unsafe { a.as_ref() }) else {
+            return -1;
+        };
+        let bytes = unsafe { slice::from_raw_parts(buf, len.max(0) as _) };
+        let decoded = unsafe { Self::state(a.plugin_data) }
+            .and_then(|s| catch_panic(P::NAME, || s.decode(bytes, a.pc, a.bits as _)).flatten());
+        let text = decoded
+            .as_ref()
+            .and_then(|d| CString::new(d.mnemonic.as_str()).ok())
+            .unwrap_or_else(|| c"invalid".to_owned());
+        unsafe {
+            rizin_sys::rz_asm_op_set_asm(op, text.as_ptr());
+            (*op).size = decoded.map_or(-1, |d| d.size as _);
+            (*op).size
+        }
+    }
+}
+
+impl RzCore {
+    /// Makes `P` selectable with `asm.arch` in this core.
+    pub fn register_arch_plugin<P: ArchPlugin>(&self) -> Result<()> {
+        let asm = plugin_for::<P, rizin_sys::RzAsmPlugin>(asm_plugin::<P>);
+        let analysis = plugin_for::<P, rizin_sys::RzAnalysisPlugin>(analysis_plugin::<P>);
+        let name = P::NAME.to_string_lossy();
+        if !unsafe { rizin_sys::rz_asm_plugin_add(self.rasm(), asm) } {
+            return Err(Error::rizin(format!("register asm plugin {}", name), None));
+        }
+        if !unsafe { rizin_sys::rz_analysis_plugin_add(self.analysis(), analysis) } {
+            // Leave nothing half registered, so a retry starts clean.
+            unsafe { rizin_sys::rz_asm_plugin_del(self.rasm(), asm) };
+            return Err(Error::rizin(
+                format!("register analysis plugin {}", name),
+                None,
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RzCore;
+    use crate::bitvector::BitVector;
+    use crate::il::{IlEffect, IlPure};
+    use crate::op::OpType;
+    use crate::plugin::arch::{ArchPlugin, DecodedOp, IlConfig};
+    use std::ffi::CStr;
+
+    /// `00` is a nop, `01 xx` jumps to the absolute byte address `xx`, `ff` panics.
+    struct Toy;
+
+    impl ArchPlugin for Toy {
+        const NAME: &'static CStr = c"rs-toy";
+        const BITS: &'static [u32] = &[16];
+
+        fn new() -> Self {
+            Toy
+        }
+
+        fn decode(&self, bytes: &[u8], addr: u64, _bits: u32) -> Option<DecodedOp> {
+            match bytes {
+                [0x00, ..] => Some(DecodedOp {
+                    size: 1,
+                    mnemonic: "nop".into(),
+                    op_type: OpType::Nop,
+                    ..Default::default()
+                }),
+                [0x01, dst, ..] => Some(DecodedOp {
+                    size: 2,
+                    mnemonic: format!("jmp {:#x}", dst),
+                    op_type: OpType::Jmp,
+                    jump: Some(*dst as u64),
+                    fail: Some(addr + 2),
+                    ..Default::default()
+                }),
+                [0xff, ..] => panic!("reserved opcode"),
+                _ => None,
+            }
+        }
+
+        fn reg_profile(&self, _bits: u32) -> String {
+            "=PC\tpc\ngpr\tpc\t.16\t0\t0\n".into()
+        }
+
+        fn lift(&self, op: &DecodedOp, _bytes: &[u8], _addr: u64) -> Option<IlEffect> {
+            Some(match op.jump {
+                Some(dst) => IlEffect::Jmp(IlPure::Bitv(BitVector::from_u64(16, dst))),
+                None => IlEffect::Nop,
+            })
+        }
+
+        fn il_config(&self, _bits: u32) -> Option<IlConfig> {
+            Some(IlConfig {
+                pc_size: 16,
+                big_endian: false,
+                mem_key_size: 16,
+            })
+        }
+    }
+
+    #[test]
+    fn test_arch_plugin() {
+        let core = RzCore::new();
+        core.register_arch_plugin::<Toy>().unwrap();
+        assert!(core.register_arch_plugin::<Toy>().is_err());
+        core.config_set("asm.arch", "rs-toy").unwrap();
+        core.config_set("asm.bits", "16").unwrap();
+
+        let insns: Vec<_> = core.disassemble(&[0x00, 0x01, 0x10], 0).collect();
+        assert_eq!(insns.len(), 2);
+        assert_eq!(insns[0].mnemonic, "nop");
+        assert_eq!(insns[1].jump, Some(0x10));
+
+        let op = core
+            .analysis_op(&[0x01, 0x10], 0, rizin_sys::RZ_ANALYSIS_OP_MASK_IL)
+            .unwrap();
+        assert_eq!(
+            op.il(),
+            Some(IlEffect::Jmp(IlPure::Bitv(BitVector::from_u64(16, 0x10))))
+        );
+
+        let err = core.analysis_op(&[0xff], 0, 0).unwrap_err();
+        assert!(err.log().is_some_and(|log| log.contains("panicked")));
+    }
+}
@@ -0,0 +1,442 @@
+use crate::RzCore;
+use crate::bin::{EntryPoint, Section, Symbol};
+use crate::error::{Error, Result};
+use crate::plugin::{catch_panic, log_error, plugin_for, rz_strdup};
+use crate::util::free;
+use std::ffi::{CStr, CString, c_char, c_void};
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::{null, null_mut};
+
+/// Bytes from the start of the file passed to [`BinPlugin::check_buffer`].
+pub const CHECK_LEN: usize = 0x1000;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BinInfo {
+    /// File type shown by `i`, e.g. `EXEC`.
+    pub file_type: String,
+    /// `asm.arch` to use for the code in the file.
+    pub arch: String,
+    pub cpu: Option<String>,
+    pub machine: Option<String>,
+    pub os: Option<String>,
+    pub bits: u32,
+    pub big_endian: bool,
+    /// Sections are mapped at virtual addresses rather than file offsets.
+    pub has_va: bool,
+}
+
+/// Binary format implemented in Rust. A value is created for every file the
+/// plugin loads and dropped when rizin unloads it.
+pub trait BinPlugin: Sized + 'static {
+    const NAME: &'static CStr;
+    const DESC: &'static CStr = c"";
+    const AUTHOR: &'static CStr = c"";
+
+    /// Whether `head`, at most [`CHECK_LEN`] bytes, starts a file of this format.
+    fn check_buffer(head: &[u8]) -> bool;
+
+    fn load(bytes: &[u8]) -> Result<Self>;
+
+    fn info(&self) -> BinInfo;
+
+    fn baddr(&self) -> u64 {
+        0
+    }
+
+    fn sections(&self) -> Vec<Section> {
+        Vec::new()
+    }
+
+    fn symbols(&self) -> Vec<Symbol> {
+        Vec::new()
+    }
+
+    fn entries(&self) -> Vec<EntryPoint> {
+        Vec::new()
+    }
+}
+
+pub const fn bin_plugin<P: BinPlugin>() -> rizin_sys::RzBinPlugin {
+    let mut p: rizin_sys::RzBinPlugin = unsafe { mem::zeroed() };
+    p.name = P::NAME.as_ptr() as _;
+    p.desc = P::DESC.as_ptr() as _;
+    p.author = P::AUTHOR.as_ptr() as _;
+    p.check_buffer = Some(Trampolines::<P>::check_buffer);
+    p.load_buffer = Some(Trampolines::<P>::load_buffer);
+    p.destroy = Some(Trampolines::<P>::destroy);
+    p.baddr = Some(Trampolines::<P>::baddr);
+    p.info = Some(Trampolines::<P>::info);
+    p.sections = Some(Trampolines::<P>::sections);
+    p.symbols = Some(Trampolines::<P>::symbols);
+    p.entries = Some(Trampolines::<P>::entries);
+    p
+}
+
+/// rizin keeps symbol binds and types as static strings, so values are mapped
+/// onto its own constants. Unknown ones become the last entry of `known`.
+fn static_str(s: Option<&str>, known: &[&'static [u8]]) -> *const c_char {
+    s.map_or(null(), |s| {
+        known
+            .iter()
+            .find(|k| k.strip_suffix(b"\0") == Some(s.as_bytes()))
+            .or(known.last())
+            .map_or(null(), |k| k.as_ptr() as _)
+    })
+}
+
+const BINDS: &[&[u8]] = &[
+    rizin_sys::RZ_BIN_BIND_GLOBAL_STR,
+    rizin_sys::RZ_BIN_BIND_LOCAL_STR,
+    rizin_sys::RZ_BIN_BIND_WEAK_STR,
+    rizin_sys::RZ_BIN_BIND_UNKNOWN_STR,
+];
+
+const TYPES: &[&[u8]] = &[
+    rizin_sys::RZ_BIN_TYPE_FUNC_STR,
+    rizin_sys::RZ_BIN_TYPE_OBJECT_STR,
+    rizin_sys::RZ_BIN_TYPE_NOTYPE_STR,
+    rizin_sys::RZ_BIN_TYPE_SECTION_STR,
+    rizin_sys::RZ_BIN_TYPE_FILE_STR,
+    rizin_sys::RZ_BIN_TYPE_UNKNOWN_STR,
+];
+
+/// Pairs items with their names for rizin, dropping those whose name has a NUL.
+fn with_cnames<P: BinPlugin, T>(items: Vec<T>, name: impl Fn(&T) -> &str) -> Vec<(CString, T)> {
+    items
+        .into_iter()
+        .filter_map(|item| match CString::new(name(&item)) {
+            Ok(cname) => Some((cname, item)),
+            Err(e) => {
+                log_error(P::NAME, &format!("dropped {:?}: {}", name(&item), e));
+                None
+            }
+        })
+        .collect()
+}
+
+fn opt_strdup(s: Option<&str>) -> *mut c_char {
+    s.map_or(null_mut(), rz_strdup)
+}
+
+unsafe extern "C" fn free_section(p: *mut c_void) {
+    unsafe { rizin_sys::rz_bin_section_free(p as _) }
+}
+
+unsafe extern "C" fn free_symbol(p: *mut c_void) {
+    unsafe { rizin_sys::rz_bin_symbol_free(p as _) }
+}
+
+/// Copies `value` into a heap block rizin releases with `free`.
+fn rz_box<T>(value: &T) -> *mut T {
+    unsafe { rizin_sys::rz_mem_dup(value as *const T as _, mem::size_of::<T>() as _) as _ }
+}
+
+fn pvector<T>(
+    items: Vec<T>,
+    free: unsafe extern "C" fn(*mut c_void),
+    mut raw: impl FnMut(T) -> *mut c_void,
+) -> *mut rizin_sys::RzPVector {
+    let vec = unsafe { rizin_sys::rz_pvector_new(Some(free)) };
+    if !vec.is_null() {
+        for item in items {
+            unsafe { rizin_sys::rz_pvector_push(vec, raw(item)) };
+        }
+    }
+    vec
+}
+
+fn read_buf(buf: *mut rizin_sys::RzBuffer, max: u64) -> Vec<u8> {
+    let len = unsafe { rizin_sys::rz_buf_size(buf) }.min(max);
+    let mut bytes = vec![0u8; len as usize];
+    let n = unsafe { rizin_sys::rz_buf_read_at(buf, 0, bytes.as_mut_ptr(), len as _) };
+    bytes.truncate(n.max(0) as usize);
+    bytes
+}
+
+struct Trampolines<P>(PhantomData<P>);
+
+impl<P: BinPlugin> Trampolines<P> {
+    unsafe fn state<'a>(bf: *mut rizin_sys::RzBinFile) -> Option<&'a P> {
+        unsafe {
+            bf.as_ref()
+                .and_then(|bf| bf.o.as_ref())
+                .and_then(|o| (o.bin_obj as *const P).as_ref())
+        }
+    }
+
+    unsafe extern "C" fn check_buffer(buf: *mut rizin_sys::RzBuffer) -> bool {
+        !buf.is_null()
+            && catch_panic(P::NAME, || {
+                P::check_buffer(&read_buf(buf, CHECK_LEN as u64))
+            })
+            .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn load_buffer(
+        _bf: *mut rizin_sys::RzBinFile,
+        obj: *mut rizin_sys::RzBinObject,
+        buf: *mut rizin_sys::RzBuffer,
+        _sdb: *mut rizin_sys::Sdb,
+    ) -> bool {
+        let Some(obj) = (unsafe { obj.as_mut() }) else {
+            return false;
+        };
+        match catch_panic(P::NAME, || P::load(&read_buf(buf, u64::MAX))) {
+            Some(Ok(state)) => {
+                obj.bin_obj = Box::into_raw(Box::new(state)) as _;
+                true
+            }
+            Some(Err(e)) => {
+                log_error(P::NAME, &e.to_string());
+                false
+            }
+            None => false,
+        }
+    }
+
+    unsafe extern "C" fn destroy(bf: *mut rizin_sys::RzBinFile) {
+        let Some(o) = (unsafe { bf.as_ref().and_then(|bf| bf.o.as_mut()) }) else {
+            return;
+        };
+        if !o.bin_obj.is_null() {
+            let state = unsafe { Box::from_raw(o.bin_obj as *mut P) };
+            o.bin_obj = null_mut();
+            catch_panic(P::NAME, || drop(state));
+        }
+    }
+
+    unsafe extern "C" fn baddr(bf: *mut rizin_sys::RzBinFile) -> u64 {
+        unsafe { Self::state(bf) }
+            .and_then(|state| catch_panic(P::NAME, || state.baddr()))
+            .unwrap_or(0)
+    }
+
+    unsafe extern "C" fn info(bf: *mut rizin_sys::RzBinFile) -> *mut rizin_sys::RzBinInfo {
+        let Some(state) = (unsafe { Self::state(bf) }) else {
+            return null_mut();
+        };
+        let Some(info) = catch_panic(P::NAME, || state.info()) else {
+            return null_mut();
+        };
+        rz_box(&rizin_sys::RzBinInfo {
+            type_: rz_strdup(&info.file_type),
+            arch: rz_strdup(&info.arch),
+            cpu: opt_strdup(info.cpu.as_deref()),
+            machine: opt_strdup(info.machine.as_deref()),
+            os: opt_strdup(info.os.as_deref()),
+            bits: info.bits as _,
+            big_endian: info.big_endian as _,
+            has_va: info.has_va as _,
+            ..Default::default()
+        })
+    }
+
+    unsafe extern "C" fn sections(bf: *mut rizin_sys::RzBinFile) -> *mut rizin_sys::RzPVector {
+        let Some(state) = (unsafe { Self::state(bf) }) else {
+            return null_mut();
+        };
+        let Some(sections) = catch_panic(P::NAME, || state.sections()) else {
+            return null_mut();
+        };
+        let sections = with_cnames::<P, _>(sections, |s| s.name.as_str());
+        pvector(sections, free_section, |(name, s)| {
+            let raw = unsafe { rizin_sys::rz_bin_section_new(name.as_ptr()) };
+            if let Some(sec) = unsafe { raw.as_mut() } {
+                sec.paddr = s.paddr;
+                sec.size = s.size;
+                sec.vaddr = s.vaddr;
+                sec.vsize = s.vsize;
+                sec.perm = s.perm.0 as _;
+            }
+            raw as _
+        })
+    }
+
+    unsafe extern "C" fn symbols(bf: *mut rizin_sys::RzBinFile) -> *mut rizin_sys::RzPVector {
+        let Some(state) = (unsafe { Self::state(bf) }) else {
+            return null_mut();
+        };
+        let Some(symbols) = catch_panic(P::NAME, || state.symbols()) else {
+            return null_mut();
+        };
+        let symbols = with_cnames::<P, _>(symbols, |s| s.name.as_str());
+        pvector(symbols, free_symbol, |(name, s)| {
+            let raw = unsafe { rizin_sys::rz_bin_symbol_new(name.as_ptr(), s.paddr, s.vaddr) };
+            if let Some(sym) = unsafe { raw.as_mut() } {
+                sym.dname = opt_strdup(s.demangled.as_deref());
+                sym.libname = opt_strdup(s.libname.as_deref());
+                sym.bind = static_str(s.bind.as_deref(), BINDS);
+                sym.type_ = static_str(s.kind.as_deref(), TYPES);
+                sym.size = s.size as _;
+                sym.ordinal = s.ordinal as _;
+                sym.is_imported = s.is_imported;
+            }
+            raw as _
+        })
+    }
+
+    unsafe extern "C" fn entries(bf: *mut rizin_sys::RzBinFile) -> *mut rizin_sys::RzPVector {
+        let Some(state) = (unsafe { Self::state(bf) }) else {
+            return null_mut();
+        };
+        let Some(entries) = catch_panic(P::NAME, || state.entries()) else {
+            return null_mut();
+        };
+        pvector(entries, free, |e| {
+            rz_box(&rizin_sys::RzBinAddr {
+                vaddr: e.vaddr,
+                paddr: e.paddr,
+                type_: e.kind.into(),
+                bits: e.bits as _,
+                ..Default::default()
+            }) as _
+        })
+    }
+}
+
+impl RzCore {
+    /// Lets the core's `RzBin` load files accepted by `P::check_buffer`.
+    pub fn register_bin_plugin<P: BinPlugin>(&self) -> Result<()> {
+        let plugin = plugin_for::<P, rizin_sys::RzBinPlugin>(bin_plugin::<P>);
+        if unsafe { rizin_sys::rz_bin_plugin_add(self.0.as_ref().bin, plugin) } {
+            Ok(())
+        } else {
+            Err(Error::rizin(
+                format!("register bin plugin {}", P::NAME.to_string_lossy()),
+                None,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bin::{BinOptions, EntryKind, EntryPoint, RzBinFile, Section, Symbol};
+    use crate::error::Error;
+    use crate::plugin::bin::{BinInfo, BinPlugin};
+    use crate::{Perm, Result, RzCore};
+    use std::ffi::CStr;
+
+    /// `FWC1`, a little endian load address, then code up to the end of the file.
+    struct Firmware {
+        base: u64,
+        code_len: u64,
+    }
+
+    impl BinPlugin for Firmware {
+        const NAME: &'static CStr = c"rs-fwc";
+
+        fn check_buffer(head: &[u8]) -> bool {
+            head.starts_with(b"FWC1")
+        }
+
+        fn load(bytes: &[u8]) -> Result<Self> {
+            let base = bytes
+                .get(4..8)
+                .ok_or_else(|| Error::rizin("short firmware header", None))?;
+            let base = u32::from_le_bytes(base.try_into().unwrap()) as u64;
+            assert_ne!(base, 0, "firmware based at zero");
+            Ok(Firmware {
+                base,
+                code_len: bytes.len() as u64 - 8,
+            })
+        }
+
+        fn info(&self) -> BinInfo {
+            BinInfo {
+                file_type: "FIRMWARE".into(),
+                arch: "x86".into(),
+                bits: 32,
+                has_va: true,
+                ..Default::default()
+            }
+        }
+
+        fn baddr(&self) -> u64 {
+            self.base
+        }
+
+        fn sections(&self) -> Vec<Section> {
+            vec![Section {
+                name: ".text".into(),
+                vaddr: self.base,
+                paddr: 8,
+                size: self.code_len,
+                vsize: self.code_len,
+                perm: Perm::R | Perm::X,
+            }]
+        }
+
+        fn symbols(&self) -> Vec<Symbol> {
+            let reset = Symbol {
+                name: "reset".into(),
+                demangled: None,
+                libname: None,
+                bind: Some("GLOBAL".into()),
+                kind: Some("FUNC".into()),
+                vaddr: self.base,
+                paddr: 8,
+                size: 1,
+                ordinal: 0,
+                is_imported: false,
+            };
+            vec![
+                Symbol {
+                    name: "vectors".into(),
+                    bind: Some("SHARED".into()),
+                    kind: Some("TABLE".into()),
+                    ordinal: 1,
+                    ..reset.clone()
+                },
+                Symbol {
+                    name: "bad\0name".into(),
+                    ordinal: 2,
+                    ..reset.clone()
+                },
+                reset,
+            ]
+        }
+
+        fn entries(&self) -> Vec<EntryPoint> {
+            vec![EntryPoint {
+                vaddr: self.base,
+                paddr: 8,
+                kind: EntryKind::Program,
+                bits: 32,
+            }]
+        }
+    }
+
+    #[test]
+    fn test_bin_plugin() {
+        let core = RzCore::new();
+        core.register_bin_plugin::<Firmware>().unwrap();
+
+        let mut bytes = b"FWC1".to_vec();
+        bytes.extend(0x8000u32.to_le_bytes());
+        bytes.extend([0x90, 0x90, 0xc3]);
+        let bf = RzBinFile::from_bytes(&core, &bytes, &BinOptions::default()).unwrap();
+
+        let sections = bf.sections();
+        assert_eq!(sections.len(), 1);
+        assert_eq!((sections[0].vaddr, sections[0].size), (0x8000, 3));
+        assert!(
+            bf.symbols()
+                .iter()
+                .any(|s| s.name == "reset" && s.is_exported())
+        );
+        let symbols = bf.symbols();
+        assert_eq!(symbols.len(), 2);
+        let vectors = symbols.iter().find(|s| s.name == "vectors").unwrap();
+        assert_eq!(vectors.bind.as_deref(), Some("UNKNOWN"));
+        assert_eq!(vectors.kind.as_deref(), Some("UNK"));
+        assert_eq!(bf.entrypoints()[0].vaddr, 0x8000);
+
+        assert!(matches!(
+            RzBinFile::from_bytes(&core, b"FWC1", &BinOptions::default()),
+            Err(Error::OpenFailed { log: Some(_), .. })
+        ));
+        assert!(RzBinFile::from_bytes(&core, b"FWC1\0\0\0\0\x90", &BinOptions::default()).is_err());
+    }
+}
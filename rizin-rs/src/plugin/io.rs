@@ -0,0 +1,226 @@
+use crate::error::{Error, Result};
+use crate::plugin::{catch_panic, leak_cstr, log_error, plugin_for};
+use crate::util::cstr_to_string;
+use crate::{Perm, RzCore};
+use std::ffi::{CStr, c_char, c_int};
+use std::io::{self, SeekFrom};
+use std::marker::PhantomData;
+use std::ptr::null_mut;
+use std::slice;
+
+/// IO backend implemented in Rust. One value is created per opened descriptor
+/// and dropped when rizin closes it.
+pub trait IoPlugin: Sized + 'static {
+    const NAME: &'static str;
+    const DESC: &'static str = "";
+    /// Comma separated uri prefixes handled by the plugin, e.g. `snap://`.
+    const URIS: &'static str;
+
+    fn check(uri: &str, _many: bool) -> bool {
+        Self::URIS
+            .split(',')
+            .any(|prefix| !prefix.is_empty() && uri.starts_with(prefix))
+    }
+
+    fn open(uri: &str, perm: Perm) -> Result<Self>;
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64>;
+
+    fn close(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Trampolines<P>(PhantomData<P>);
+
+impl<P: IoPlugin> Trampolines<P> {
+    fn plugin() -> *mut rizin_sys::RzIOPlugin {
+        plugin_for::<P, _>(|| rizin_sys::RzIOPlugin {
+            name: leak_cstr(P::NAME),
+            desc: leak_cstr(P::DESC),
+            uris: leak_cstr(P::URIS),
+            open: Some(Self::open),
+            read: Some(Self::read),
+            write: Some(Self::write),
+            lseek: Some(Self::lseek),
+            close: Some(Self::close),
+            check: Some(Self::check),
+            ..Default::default()
+        })
+    }
+
+    fn name() -> &'static CStr {
+        unsafe { CStr::from_ptr((*Self::plugin()).name) }
+    }
+
+    unsafe fn state<'a>(fd: *mut rizin_sys::RzIODesc) -> Option<&'a mut P> {
+        unsafe { fd.as_ref().and_then(|fd| (fd.data as *mut P).as_mut()) }
+    }
+
+    unsafe extern "C" fn check(_io: *mut rizin_sys::RzIO, uri: *const c_char, many: bool) -> bool {
+        cstr_to_string(uri)
+            .and_then(|uri| catch_panic(Self::name(), || P::check(&uri, many)))
+            .unwrap_or(false)
+    }
+
+    unsafe extern "C" fn open(
+        io: *mut rizin_sys::RzIO,
+        uri: *const c_char,
+        perm: c_int,
+        mode: c_int,
+    ) -> *mut rizin_sys::RzIODesc {
+        let Some(uri_str) = cstr_to_string(uri) else {
+            return null_mut();
+        };
+        let state = match catch_panic(Self::name(), || P::open(&uri_str, Perm(perm as u32))) {
+            Some(Ok(state)) => state,
+            Some(Err(e)) => {
+                log_error(Self::name(), &e.to_string());
+                return null_mut();
+            }
+            None => return null_mut(),
+        };
+        let data = Box::into_raw(Box::new(state));
+        let desc =
+            unsafe { rizin_sys::rz_io_desc_new(io, Self::plugin(), uri, perm, mode, data as _) };
+        if desc.is_null() {
+            let state = unsafe { Box::from_raw(data) };
+            catch_panic(Self::name(), || drop(state));
+        }
+        desc
+    }
+
+    unsafe extern "C" fn read(
+        _io: *mut rizin_sys::RzIO,
+        fd: *mut rizin_sys::RzIODesc,
+        buf: *mut u8,
+        count: c_int,
+    ) -> c_int {
+        let Some(state) = (unsafe { Self::state(fd) }) else {
+            return -1;
+        };
+        let buf = unsafe { slice::from_raw_parts_mut(buf, count.max(0) as _) };
+        catch_panic(Self::name(), || state.read(buf))
+            .and_then(|res| res.ok())
+            .map_or(-1, |n| n as _)
+    }
+
+    unsafe extern "C" fn write(
+        _io: *mut rizin_sys::RzIO,
+        fd: *mut rizin_sys::RzIODesc,
+        buf: *const u8,
+        count: c_int,
+    ) -> c_int {
+        let Some(state) = (unsafe { Self::state(fd) }) else {
+            return -1;
+        };
+        let buf = unsafe { slice::from_raw_parts(buf, count.max(0) as _) };
+        catch_panic(Self::name(), || state.write(buf))
+            .and_then(|res| res.ok())
+            .map_or(-1, |n| n as _)
+    }
+
+    unsafe extern "C" fn lseek(
+        _io: *mut rizin_sys::RzIO,
+        fd: *mut rizin_sys::RzIODesc,
+        offset: u64,
+        whence: c_int,
+    ) -> u64 {
+        let Some(state) = (unsafe { Self::state(fd) }) else {
+            return u64::MAX;
+        };
+        let pos = match whence as u32 {
+            rizin_sys::RZ_IO_SEEK_SET => SeekFrom::Start(offset),
+            rizin_sys::RZ_IO_SEEK_CUR => SeekFrom::Current(offset as i64),
+            rizin_sys::RZ_IO_SEEK_END => SeekFrom::End(offset as i64),
+            _ => return u64::MAX,
+        };
+        catch_panic(Self::name(), || state.seek(pos))
+            .and_then(|res| res.ok())
+            .unwrap_or(u64::MAX)
+    }
+
+    unsafe extern "C" fn close(fd: *mut rizin_sys::RzIODesc) -> c_int {
+        let Some(fd) = (unsafe { fd.as_mut() }) else {
+            return -1;
+        };
+        if fd.data.is_null() {
+            return 0;
+        }
+        let mut state = unsafe { Box::from_raw(fd.data as *mut P) };
+        fd.data = null_mut();
+        catch_panic(Self::name(), move || state.close())
+            .and_then(|res| res.ok())
+            .map_or(-1, |_| 0)
+    }
+}
+
+impl RzCore {
+    /// Makes uris accepted by `P::check` openable through [`RzCore::open`].
+    pub fn register_io_plugin<P: IoPlugin>(&self) -> Result<()> {
+        if unsafe { rizin_sys::rz_io_plugin_add(self.io(), Trampolines::<P>::plugin()) } {
+            Ok(())
+        } else {
+            Err(Error::rizin(
+                format!("register io plugin {}", P::NAME),
+                None,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::plugin::io::IoPlugin;
+    use crate::{Error, Perm, Result, RzCore};
+    use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+
+    struct Pattern(Cursor<Vec<u8>>);
+
+    impl IoPlugin for Pattern {
+        const NAME: &'static str = "rs-pattern";
+        const URIS: &'static str = "pattern://";
+
+        fn open(uri: &str, _perm: Perm) -> Result<Self> {
+            let len: usize = uri["pattern://".len()..]
+                .parse()
+                .map_err(|_| Error::InvalidPath(uri.into()))?;
+            Ok(Pattern(Cursor::new((0..len).map(|i| i as u8).collect())))
+        }
+
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            assert!(self.0.position() < 0x100, "read past the pattern");
+            self.0.read(buf)
+        }
+
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.write(buf)
+        }
+
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.0.seek(pos)
+        }
+    }
+
+    #[test]
+    fn test_io_plugin() {
+        let core = RzCore::new();
+        core.register_io_plugin::<Pattern>().unwrap();
+        assert!(core.register_io_plugin::<Pattern>().is_err());
+
+        let file = core.open("pattern://32", Perm::RW, 0x2000).unwrap();
+        assert_eq!(file.size(), 32);
+        assert_eq!(core.read_at(0x2004, 4).unwrap(), vec![4, 5, 6, 7]);
+        core.write_at(0x2000, &[0xaa]).unwrap();
+        assert_eq!(file.read_at(0, 2).unwrap(), vec![0xaa, 1]);
+
+        let err = core.open("pattern://many", Perm::R, 0).unwrap_err();
+        assert!(err.log().is_some_and(|log| log.contains("pattern://many")));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::RzCore;
+use crate::error::{Error, Result};
+use crate::util::{RzList, cstr_to_string, free, raw_enum};
+use std::collections::hash_map::RandomState;
+use std::env;
+use std::ffi::{CString, c_char};
+use std::fs::{self, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::ptr::null;
+
+raw_enum!(
+    /// Why rizin rejected a project file.
+    ProjectError: rizin_sys::RzProjectErr {
+        File = RZ_PROJECT_ERR_FILE,
+        InvalidType = RZ_PROJECT_ERR_INVALID_TYPE,
+        InvalidVersion = RZ_PROJECT_ERR_INVALID_VERSION,
+        NewerVersion = RZ_PROJECT_ERR_NEWER_VERSION,
+        InvalidContents = RZ_PROJECT_ERR_INVALID_CONTENTS,
+        MigrationFailed = RZ_PROJECT_ERR_MIGRATION_FAILED,
+        CompressionFailed = RZ_PROJECT_ERR_COMPRESSION_FAILED,
+        Unknown = RZ_PROJECT_ERR_UNKNOWN,
+    }
+);
+
+impl ProjectError {
+    pub fn message(self) -> Option<String> {
+        cstr_to_string(unsafe { rizin_sys::rz_project_err_message(self.into()) })
+    }
+}
+
+/// Project file in the temp dir, removed on drop. Created exclusively under a
+/// random name so nothing else can have it open.
+struct TempProject(PathBuf);
+
+impl TempProject {
+    fn new() -> Result<Self> {
+        loop {
+            let n = RandomState::new().build_hasher().finish();
+            let path = env::temp_dir().join(format!("rizin-rs-{}-{:016x}.rzdb", process::id(), n));
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+            match options.open(&path) {
+                Ok(_) => return Ok(Self(path)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for TempProject {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn project_path(path: &Path) -> Result<CString> {
+    let spath = path
+        .to_str()
+        .ok_or_else(|| Error::InvalidPath(path.to_owned()))?;
+    Ok(CString::new(spath)?)
+}
+
+fn new_results() -> RzList<c_char> {
+    RzList::from_raw(unsafe { rizin_sys::rz_list_newf(Some(free)) }).expect("null ptr")
+}
+
+fn details(res: &RzList<c_char>) -> Vec<String> {
+    res.iter()
+        .filter_map(|msg| cstr_to_string(msg as *const c_char))
+        .collect()
+}
+
+fn check(path: &Path, err: rizin_sys::RzProjectErr, details: Vec<String>) -> Result<()> {
+    if err == rizin_sys::RZ_PROJECT_ERR_SUCCESS {
+        Ok(())
+    } else {
+        Err(Error::ProjectFailed {
+            path: path.to_owned(),
+            kind: err.into(),
+            details,
+        })
+    }
+}
+
+impl RzCore {
+    /// Writes the analysis, flags, config and opened files to an `.rzdb` project.
+    pub fn save_project(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let cpath = project_path(path)?;
+        let err =
+            unsafe { rizin_sys::rz_project_save_file(self.0.as_ptr(), cpath.as_ptr(), false) };
+        check(path, err, Vec::new())
+    }
+
+    /// Loads a project into this core, migrating files saved by older rizin
+    /// versions. Migration messages are returned in the error on failure.
+    pub fn load_project(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let cpath = project_path(path)?;
+        let res = new_results();
+        let err = unsafe {
+            rizin_sys::rz_project_load_file(
+                self.0.as_ptr(),
+                cpath.as_ptr(),
+                true,
+                res.inner.as_ptr(),
+            )
+        };
+        check(path, err, details(&res))
+    }
+
+    /// Serialized project, for storage outside the filesystem. Opened files are
+    /// recorded with absolute paths since there is no project file to be relative to.
+    pub fn save_project_bytes(&self) -> Result<Vec<u8>> {
+        let tmp = TempProject::new()?;
+        let cpath = project_path(&tmp.0)?;
+        let prj = unsafe { rizin_sys::sdb_new0() };
+        if prj.is_null() {
+            return Err(Error::NullPointer("project sdb"));
+        }
+        // sdb only writes its text form to a file.
+        let err = unsafe {
+            match rizin_sys::rz_project_save(self.0.as_ptr(), prj, null()) {
+                rizin_sys::RZ_PROJECT_ERR_SUCCESS
+                    if !rizin_sys::sdb_text_save(prj, cpath.as_ptr(), true) =>
+                {
+                    rizin_sys::RZ_PROJECT_ERR_FILE
+                }
+                err => err,
+            }
+        };
+        unsafe { rizin_sys::rz_project_free(prj) };
+        check(&tmp.0, err, Vec::new())?;
+        Ok(fs::read(&tmp.0)?)
+    }
+
+    /// Loads a project produced by [`RzCore::save_project_bytes`] without going
+    /// through a file.
+    pub fn load_project_bytes(&self, bytes: &[u8]) -> Result<()> {
+        let path = Path::new("<memory>");
+        let prj = unsafe { rizin_sys::sdb_new0() };
+        if prj.is_null() {
+            return Err(Error::NullPointer("project sdb"));
+        }
+        // The text parser writes into the buffer it reads.
+        let mut buf = bytes.to_vec();
+        buf.push(0);
+        let res = new_results();
+        let err = unsafe {
+            if rizin_sys::sdb_text_load_buf(prj, buf.as_mut_ptr() as _, bytes.len()) {
+                rizin_sys::rz_project_load(self.0.as_ptr(), prj, true, null(), res.inner.as_ptr())
+            } else {
+                rizin_sys::RZ_PROJECT_ERR_INVALID_CONTENTS
+            }
+        };
+        unsafe { rizin_sys::rz_project_free(prj) };
+        check(path, err, details(&res))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::project::ProjectError;
+    use crate::{Error, RzCore};
+
+    #[test]
+    fn test_project_roundtrip() {
+        let core = RzCore::new();
+        core.add_flag("sym.main", 0x1000, 16).unwrap();
+        core.set_comment(0x1000, "entry").unwrap();
+        let bytes = core.save_project_bytes().unwrap();
+
+        let other = RzCore::new();
+        other.load_project_bytes(&bytes).unwrap();
+        assert_eq!(other.flag_by_name("sym.main").unwrap().offset, 0x1000);
+        assert_eq!(other.comment(0x1000).as_deref(), Some("entry"));
+
+        assert!(matches!(
+            other.load_project("/nonexistent/project.rzdb"),
+            Err(Error::ProjectFailed {
+                kind: ProjectError::File,
+                ..
+            })
+        ));
+        assert!(matches!(
+            other.load_project_bytes(b"not a project"),
+            Err(Error::ProjectFailed { .. })
+        ));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::RzCore;
+use crate::bitvector::BitVector;
+use crate::error::{Error, Result};
+use crate::util::{RzList, cstr_to_string, raw_enum};
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ptr::{NonNull, null};
+
+raw_enum!(
+    /// Register bank, as in the `gpr`/`fpu`/`flg` column of a profile.
+    RegType: rizin_sys::RzRegisterType {
+        Gpr = RZ_REG_TYPE_GPR,
+        Drx = RZ_REG_TYPE_DRX,
+        Fpu = RZ_REG_TYPE_FPU,
+        Mmx = RZ_REG_TYPE_MMX,
+        Xmm = RZ_REG_TYPE_XMM,
+        Ymm = RZ_REG_TYPE_YMM,
+        Flg = RZ_REG_TYPE_FLG,
+        Seg = RZ_REG_TYPE_SEG,
+        Sys = RZ_REG_TYPE_SYS,
+        Sec = RZ_REG_TYPE_SEC,
+        Vc = RZ_REG_TYPE_VC,
+        Vcc = RZ_REG_TYPE_VCC,
+        Vcz = RZ_REG_TYPE_VCZ,
+    }
+);
+
+raw_enum!(
+    /// Alias a profile assigns with `=PC`, `=SP`, `=A0` and so on.
+    RegRole: rizin_sys::RzRegisterId {
+        Pc = RZ_REG_NAME_PC,
+        Sp = RZ_REG_NAME_SP,
+        Sr = RZ_REG_NAME_SR,
+        Bp = RZ_REG_NAME_BP,
+        Lr = RZ_REG_NAME_LR,
+        A0 = RZ_REG_NAME_A0,
+        A1 = RZ_REG_NAME_A1,
+        A2 = RZ_REG_NAME_A2,
+        A3 = RZ_REG_NAME_A3,
+        A4 = RZ_REG_NAME_A4,
+        A5 = RZ_REG_NAME_A5,
+        A6 = RZ_REG_NAME_A6,
+        A7 = RZ_REG_NAME_A7,
+        A8 = RZ_REG_NAME_A8,
+        A9 = RZ_REG_NAME_A9,
+        R0 = RZ_REG_NAME_R0,
+        R1 = RZ_REG_NAME_R1,
+        R2 = RZ_REG_NAME_R2,
+        R3 = RZ_REG_NAME_R3,
+        Zf = RZ_REG_NAME_ZF,
+        Sf = RZ_REG_NAME_SF,
+        Cf = RZ_REG_NAME_CF,
+        Of = RZ_REG_NAME_OF,
+        Sn = RZ_REG_NAME_SN,
+    }
+);
+
+impl RegRole {
+    const ALL: [RegRole; 24] = [
+        RegRole::Pc,
+        RegRole::Sp,
+        RegRole::Sr,
+        RegRole::Bp,
+        RegRole::Lr,
+        RegRole::A0,
+        RegRole::A1,
+        RegRole::A2,
+        RegRole::A3,
+        RegRole::A4,
+        RegRole::A5,
+        RegRole::A6,
+        RegRole::A7,
+        RegRole::A8,
+        RegRole::A9,
+        RegRole::R0,
+        RegRole::R1,
+        RegRole::R2,
+        RegRole::R3,
+        RegRole::Zf,
+        RegRole::Sf,
+        RegRole::Cf,
+        RegRole::Of,
+        RegRole::Sn,
+    ];
+}
+
+/// A register of the profile. `size` and `offset` are in bits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Register {
+    pub name: String,
+    pub role: Option<RegRole>,
+    pub kind: RegType,
+    pub size: u32,
+    pub offset: u32,
+}
+
+/// The core's register file, with the profile of the current arch and bits.
+pub struct RegisterFile<'a> {
+    inner: NonNull<rizin_sys::RzReg>,
+    marker: PhantomData<&'a RzCore>,
+}
+
+impl<'a> RegisterFile<'a> {
+    /// Loads the arch plugin's profile if the core has none yet.
+    pub fn new(core: &'a RzCore) -> Result<Self> {
+        let analysis = core.analysis();
+        let regs = Self::from_raw(unsafe { (*analysis).reg })
+            .ok_or(Error::NullPointer("analysis register file"))?;
+        if regs.all().is_empty()
+            && !unsafe { rizin_sys::rz_analysis_set_reg_profile(analysis, null()) }
+        {
+            return Err(Error::rizin("load register profile", None));
+        }
+        Ok(regs)
+    }
+
+    pub(crate) fn from_raw(reg: *mut rizin_sys::RzReg) -> Option<Self> {
+        NonNull::new(reg).map(|inner| Self {
+            inner,
+            marker: PhantomData,
+        })
+    }
+
+    /// The pointer stays owned by the core; it is valid while the core is.
+    pub fn as_ptr(&self) -> *mut rizin_sys::RzReg {
+        self.inner.as_ptr()
+    }
+
+    fn all(&self) -> Vec<&rizin_sys::RzRegItem> {
+        let list =
+            unsafe { rizin_sys::rz_reg_get_list(self.inner.as_ptr(), rizin_sys::RZ_REG_TYPE_ANY) };
+        RzList::<rizin_sys::RzRegItem>::from_raw(list as _)
+            .map(ManuallyDrop::new)
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|item| unsafe { &*(item as *const rizin_sys::RzRegItem) })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn item(&self, name: &str) -> Result<*mut rizin_sys::RzRegItem> {
+        let cname = CString::new(name)?;
+        let item = unsafe {
+            rizin_sys::rz_reg_get(
+                self.inner.as_ptr(),
+                cname.as_ptr(),
+                rizin_sys::RZ_REG_TYPE_ANY as _,
+            )
+        };
+        if item.is_null() {
+            Err(Error::UnknownRegister(name.to_owned()))
+        } else {
+            Ok(item)
+        }
+    }
+
+    /// Name of the register the profile assigns to `role`.
+    pub fn role_name(&self, role: RegRole) -> Option<String> {
+        cstr_to_string(unsafe { rizin_sys::rz_reg_get_name(self.inner.as_ptr(), role.into()) })
+    }
+
+    pub fn registers(&self) -> Vec<Register> {
+        let roles: Vec<_> = RegRole::ALL
+            .iter()
+            .filter_map(|&role| Some((self.role_name(role)?, role)))
+            .collect();
+        self.all()
+            .into_iter()
+            .filter_map(|item| {
+                let name = cstr_to_string(item.name)?;
+                Some(Register {
+                    role: roles.iter().find(|(n, _)| *n == name).map(|(_, r)| *r),
+                    name,
+                    kind: item.type_.into(),
+                    size: item.size as _,
+                    offset: item.offset as _,
+                })
+            })
+            .collect()
+    }
+
+    /// Value truncated to 64 bits; use [`RegisterFile::get_bv`] for wider registers.
+    pub fn get(&self, name: &str) -> Result<u64> {
+        let item = self.item(name)?;
+        Ok(unsafe { rizin_sys::rz_reg_get_value(self.inner.as_ptr(), item) })
+    }
+
+    pub fn set(&mut self, name: &str, value: u64) -> Result<()> {
+        let item = self.item(name)?;
+        if unsafe { rizin_sys::rz_reg_set_value(self.inner.as_ptr(), item, value) } {
+            Ok(())
+        } else {
+            Err(Error::rizin(format!("set register {}", name), None))
+        }
+    }
+
+    pub fn get_bv(&self, name: &str) -> Result<BitVector> {
+        let item = self.item(name)?;
+        BitVector::from_raw(unsafe { rizin_sys::rz_reg_get_bv(self.inner.as_ptr(), item) })
+            .ok_or_else(|| Error::rizin(format!("get register {}", name), None))
+    }
+
+    /// `value` must be as wide as the register.
+    pub fn set_bv(&mut self, name: &str, value: &BitVector) -> Result<()> {
+        let item = self.item(name)?;
+        if unsafe { rizin_sys::rz_reg_set_bv(self.inner.as_ptr(), item, value.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(Error::rizin(format!("set register {}", name), None))
+        }
+    }
+
+    pub fn get_role(&self, role: RegRole) -> Result<u64> {
+        let name = self
+            .role_name(role)
+            .ok_or_else(|| Error::UnknownRegister(format!("{:?}", role)))?;
+        self.get(&name)
+    }
+
+    /// Saves the register state on the arena stack.
+    pub fn push(&mut self) {
+        unsafe { rizin_sys::rz_reg_arena_push(self.inner.as_ptr()) };
+    }
+
+    /// Restores the state saved by the matching [`RegisterFile::push`].
+    pub fn pop(&mut self) {
+        unsafe { rizin_sys::rz_reg_arena_pop(self.inner.as_ptr()) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitvector::BitVector;
+    use crate::reg::{RegRole, RegType, RegisterFile};
+    use crate::{Error, RzCore};
+
+    #[test]
+    fn test_registers() {
+        let core = RzCore::new();
+        core.config_set("asm.arch", "x86").unwrap();
+        core.config_set("asm.bits", "32").unwrap();
+        let mut regs = RegisterFile::new(&core).unwrap();
+        assert_eq!(regs.role_name(RegRole::Pc).as_deref(), Some("eip"));
+        let eax = regs
+            .registers()
+            .into_iter()
+            .find(|r| r.name == "eax")
+            .unwrap();
+        assert_eq!(eax.kind, RegType::Gpr);
+        assert_eq!(eax.size, 32);
+        assert_eq!(eax.role, Some(RegRole::A0));
+
+        regs.set("eax", 0x1234).unwrap();
+        regs.set("eip", 0x8000).unwrap();
+        assert_eq!(regs.get("ax").unwrap(), 0x1234);
+        assert_eq!(regs.get_role(RegRole::Pc).unwrap(), 0x8000);
+        assert_eq!(regs.get_bv("eax").unwrap(), BitVector::from_u64(32, 0x1234));
+
+        regs.push();
+        regs.set_bv("eax", &BitVector::from_u64(32, 0xdead))
+            .unwrap();
+        assert_eq!(regs.get("eax").unwrap(), 0xdead);
+        regs.pop();
+        assert_eq!(regs.get("eax").unwrap(), 0x1234);
+
+        assert!(matches!(regs.get("nope"), Err(Error::UnknownRegister(_))));
+    }
+}
@@ -0,0 +1,21 @@
+use crate::io::IoFile;
+use crate::{Perm, RzCore};
+
+/// `main` at 0x1000 calls `f` at 0x1006, which branches on `eax`.
+pub(crate) const CODE: [u8; 12] = [
+    0xe8, 0x01, 0x00, 0x00, 0x00, // call 0x1006
+    0xc3, // ret
+    0x85, 0xc0, // test eax, eax
+    0x74, 0x01, // je 0x100b
+    0x40, // inc eax
+    0xc3, // ret
+];
+
+/// Maps [`CODE`] at 0x1000 as x86-32, unanalyzed.
+pub(crate) fn load_code(core: &RzCore) -> IoFile<'_> {
+    core.config_set("asm.arch", "x86").unwrap();
+    core.config_set("asm.bits", "32").unwrap();
+    let file = core.open("malloc://0x100", Perm::RWX, 0x1000).unwrap();
+    core.write_at(0x1000, &CODE).unwrap();
+    file
+}
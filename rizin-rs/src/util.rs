@@ -1,5 +1,5 @@
 use rizin_sys::rz_iterator_next;
-use std::ffi::CStr;
+use std::ffi::{CStr, c_char, c_void};
 use std::fmt::Display;
 use std::marker::PhantomData;
 use std::mem::ManuallyDrop;
@@ -51,6 +51,29 @@ impl<T> RzIterator<'_, T> {
     }
 }
 
+pub(crate) fn cstr_to_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(
+            unsafe { CStr::from_ptr(ptr) }
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+}
+
+unsafe extern "C" {
+    pub(crate) fn free(ptr: *mut c_void);
+}
+
+/// Copies a heap string returned by rizin and releases the original.
+pub(crate) fn owned_cstr_to_string(ptr: *mut c_char) -> Option<String> {
+    let s = cstr_to_string(ptr);
+    unsafe { free(ptr as _) };
+    s
+}
+
 pub struct RzStrBuf(pub rizin_sys::RzStrBuf);
 
 impl RzStrBuf {
@@ -97,7 +120,7 @@ pub struct RzListIter<'a, T: 'a> {
 }
 
 impl<T> RzList<T> {
-    fn from_raw(value: *mut rizin_sys::RzList) -> Option<Self> {
+    pub(crate) fn from_raw(value: *mut rizin_sys::RzList) -> Option<Self> {
         NonNull::new(value).map(|inner| Self {
             inner,
             marker: PhantomData,
@@ -315,7 +338,7 @@ pub struct RzPVector<T> {
 }
 
 impl<T> RzPVector<T> {
-    fn from_raw(value: *mut rizin_sys::RzPVector) -> Option<Self> {
+    pub(crate) fn from_raw(value: *mut rizin_sys::RzPVector) -> Option<Self> {
         NonNull::new(value).and_then(|inner| {
             let v = unsafe { RzVector::from_raw(addr_of_mut!((*inner.as_ptr()).v))? };
             Some(Self {
@@ -360,6 +383,39 @@ impl<T> DerefMut for RzPVector<T> {
     }
 }
 
+/// Declares an enum mirroring a set of rizin constants, with an `Other` fallback
+/// and conversions in both directions.
+macro_rules! raw_enum {
+    ($(#[$meta:meta])* $name:ident: $raw:ty { $($variant:ident = $c:ident),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)*
+            Other($raw),
+        }
+
+        impl From<$raw> for $name {
+            fn from(value: $raw) -> Self {
+                match value {
+                    $(rizin_sys::$c => $name::$variant,)*
+                    v => $name::Other(v),
+                }
+            }
+        }
+
+        impl From<$name> for $raw {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => rizin_sys::$c,)*
+                    $name::Other(v) => v,
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use raw_enum;
+
 #[cfg(test)]
 mod tests {
     use crate::RzCore;
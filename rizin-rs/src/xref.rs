@@ -0,0 +1,134 @@
+use crate::RzCore;
+use crate::error::{Error, Result, with_log};
+use crate::util::RzList;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum XRefKind {
+    Null,
+    Code,
+    Call,
+    Data,
+    String,
+}
+
+impl From<rizin_sys::RzAnalysisXRefType> for XRefKind {
+    fn from(value: rizin_sys::RzAnalysisXRefType) -> Self {
+        match value {
+            rizin_sys::RZ_ANALYSIS_XREF_TYPE_CODE => XRefKind::Code,
+            rizin_sys::RZ_ANALYSIS_XREF_TYPE_CALL => XRefKind::Call,
+            rizin_sys::RZ_ANALYSIS_XREF_TYPE_DATA => XRefKind::Data,
+            rizin_sys::RZ_ANALYSIS_XREF_TYPE_STRING => XRefKind::String,
+            _ => XRefKind::Null,
+        }
+    }
+}
+
+impl From<XRefKind> for rizin_sys::RzAnalysisXRefType {
+    fn from(value: XRefKind) -> Self {
+        match value {
+            XRefKind::Null => rizin_sys::RZ_ANALYSIS_XREF_TYPE_NULL,
+            XRefKind::Code => rizin_sys::RZ_ANALYSIS_XREF_TYPE_CODE,
+            XRefKind::Call => rizin_sys::RZ_ANALYSIS_XREF_TYPE_CALL,
+            XRefKind::Data => rizin_sys::RZ_ANALYSIS_XREF_TYPE_DATA,
+            XRefKind::String => rizin_sys::RZ_ANALYSIS_XREF_TYPE_STRING,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct XRef {
+    pub from: u64,
+    pub to: u64,
+    pub kind: XRefKind,
+}
+
+impl From<&rizin_sys::RzAnalysisXRef> for XRef {
+    fn from(x: &rizin_sys::RzAnalysisXRef) -> Self {
+        Self {
+            from: x.from,
+            to: x.to,
+            kind: x.type_.into(),
+        }
+    }
+}
+
+/// Converts and frees a list of xrefs returned by rizin.
+pub(crate) fn collect_xrefs(list: *mut rizin_sys::RzList) -> Vec<XRef> {
+    RzList::<rizin_sys::RzAnalysisXRef>::from_raw(list)
+        .map(|xrefs| xrefs.iter().map(XRef::from).collect())
+        .unwrap_or_default()
+}
+
+impl RzCore {
+    pub fn xrefs_to(&self, addr: u64) -> Vec<XRef> {
+        collect_xrefs(unsafe { rizin_sys::rz_analysis_xrefs_get_to(self.analysis(), addr) })
+    }
+
+    pub fn xrefs_from(&self, addr: u64) -> Vec<XRef> {
+        collect_xrefs(unsafe { rizin_sys::rz_analysis_xrefs_get_from(self.analysis(), addr) })
+    }
+
+    pub fn add_xref(&self, from: u64, to: u64, kind: XRefKind) -> Result<()> {
+        let (ok, log) = with_log(|| unsafe {
+            rizin_sys::rz_analysis_xrefs_set(self.analysis(), from, to, kind.into())
+        });
+        if ok {
+            Ok(())
+        } else {
+            Err(Error::rizin(
+                format!("add xref {:#x} -> {:#x}", from, to),
+                log,
+            ))
+        }
+    }
+
+    pub fn remove_xref(&self, from: u64, to: u64) -> Result<()> {
+        let (ok, log) =
+            with_log(|| unsafe { rizin_sys::rz_analysis_xref_del(self.analysis(), from, to) });
+        if ok {
+            Ok(())
+        } else {
+            Err(Error::rizin(
+                format!("remove xref {:#x} -> {:#x}", from, to),
+                log,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RzCore;
+    use crate::xref::{XRef, XRefKind};
+
+    #[test]
+    fn test_xrefs() {
+        let core = RzCore::new();
+        core.add_xref(0x1000, 0x2000, XRefKind::Call).unwrap();
+        core.add_xref(0x1010, 0x2000, XRefKind::Data).unwrap();
+        core.add_xref(0x1000, 0x3000, XRefKind::Code).unwrap();
+
+        let mut to = core.xrefs_to(0x2000);
+        to.sort_by_key(|x| x.from);
+        assert_eq!(
+            to,
+            vec![
+                XRef {
+                    from: 0x1000,
+                    to: 0x2000,
+                    kind: XRefKind::Call
+                },
+                XRef {
+                    from: 0x1010,
+                    to: 0x2000,
+                    kind: XRefKind::Data
+                },
+            ]
+        );
+        assert_eq!(core.xrefs_from(0x1000).len(), 2);
+
+        core.remove_xref(0x1000, 0x2000).unwrap();
+        assert_eq!(core.xrefs_to(0x2000).len(), 1);
+        assert_eq!(core.xrefs_from(0x1000).len(), 1);
+    }
+}
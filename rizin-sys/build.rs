@@ -37,6 +37,7 @@ const RZ_LIBRARIES: &[&str] = &[
 fn main() -> Result<(), Box<dyn Error>> {
     let mut builder = bindgen::Builder::default();
     let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let mut include_dirs = Vec::new();
 
     let lib_name = "rz_core";
     if let Ok(librz) = pkg_config::Config::new()
@@ -44,12 +45,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         // .statik(true)            // 需要静态链接时可打开
         .probe(lib_name)
     {
-        builder = builder.clang_args(
-            librz
-                .include_paths
-                .iter()
-                .map(|p| format!("-I{}", p.to_str().unwrap())),
-        );
+        include_dirs = librz.include_paths;
     } else {
         for lib in RZ_LIBRARIES {
             println!("cargo:rustc-link-lib=dylib={}", lib);
@@ -60,18 +56,27 @@ fn main() -> Result<(), Box<dyn Error>> {
         if let Ok(dir) = env::var("RIZIN_DIR") {
             let rizin_dir = PathBuf::from(dir);
             let inc_dir = rizin_dir.join("include");
-            builder = builder.clang_args([
-                "-I",
-                inc_dir.to_str().unwrap(),
-                "-I",
-                inc_dir.join("librz").to_str().unwrap(),
-                "-I",
-                inc_dir.join("librz").join("sdb").to_str().unwrap(),
-            ])
+            include_dirs = vec![
+                inc_dir.clone(),
+                inc_dir.join("librz"),
+                inc_dir.join("librz").join("sdb"),
+            ];
         }
     }
+    builder = builder.clang_args(
+        include_dirs
+            .iter()
+            .map(|p| format!("-I{}", p.to_str().unwrap())),
+    );
 
     println!("cargo:rerun-if-changed=wrapper.h");
+    println!("cargo:rerun-if-changed=shim/log.c");
+
+    cc::Build::new()
+        .file("shim/log.c")
+        .includes(&include_dirs)
+        .flag_if_supported("-Wno-unused-parameter")
+        .compile("rizin_sys_shim");
 
     let bindings = builder
         .derive_default(true)
@@ -86,8 +91,10 @@ fn main() -> Result<(), Box<dyn Error>> {
         .allowlist_type("rz_.*")
         .allowlist_type("Rz.*")
         .allowlist_function("rz_.*")
+        .allowlist_function("sdb_.*")
         .allowlist_var("rz.*")
         .allowlist_var("RZ.*")
+        .allowlist_var("CN_.*")
         .clang_arg("-fparse-all-comments")
         .clang_arg("-std=c99")
         .generate()
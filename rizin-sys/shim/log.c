@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: LGPL-3.0-only
+
+// RzLogCallback is variadic, which Rust cannot define on stable. This forwards
+// the already formatted message to a plain function pointer instead.
+
+#include <rz_util/rz_log.h>
+
+typedef void (*RizinSysLogSink)(const char *output, RzLogLevel level);
+
+static RizinSysLogSink log_sink;
+
+static void log_trampoline(const char *output, const char *funcname, const char *filename,
+	ut32 lineno, RzLogLevel level, const char *tag, const char *fmtstr, ...) {
+	if (log_sink) {
+		log_sink(output, level);
+	}
+}
+
+void rizin_sys_set_log_sink(RizinSysLogSink sink) {
+	if (!log_sink) {
+		rz_log_add_callback(log_trampoline);
+	}
+	log_sink = sink;
+}
@@ -5,6 +5,16 @@
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
+/// Receives every message rizin logs, already formatted.
+pub type RizinSysLogSink =
+    Option<unsafe extern "C" fn(output: *const ::std::os::raw::c_char, level: RzLogLevel)>;
+
+unsafe extern "C" {
+    /// Registers a log callback with rizin that forwards to `sink`, from `shim/log.c`.
+    /// Later calls only replace the sink.
+    pub fn rizin_sys_set_log_sink(sink: RizinSysLogSink);
+}
+
 #[cfg(test)]
 mod tests {
     use std::ffi::CString;